@@ -1,6 +1,80 @@
 use crate::messages::{CodePayload, PlanPayload};
 use crate::thinking::{ThinkingTimer, ProcessingStage};
 
+/// A progressively-revealed code template. Pass 1 (outline), Pass 2
+/// (draft), and Pass 3 (refinement) each render from a different subset of
+/// this, so every pass is a real transformation of the last instead of
+/// three identical calls to the same generator.
+struct CodeTemplate {
+    /// Bare signatures (struct/enum/impl/fn headers) with `unimplemented!()`
+    /// bodies and no `main`. This is the whole of Pass 1.
+    signatures: String,
+    /// Working code with `main`, but with `// TODO: ...` markers standing
+    /// in for whatever `edge_cases` will later fill in, and no doc
+    /// comments. This is the whole of Pass 2.
+    body: String,
+    /// `(marker, replacement)` pairs: each `marker` is a `// TODO: ...`
+    /// line present in `body`, replaced by `replacement` to produce Pass 3.
+    edge_cases: Vec<(String, String)>,
+    /// Doc comments, one per struct/enum/fn item in `body` (excluding
+    /// `main`), in the order those items appear. Pass 3 prepends each to
+    /// its item.
+    docs: Vec<String>,
+}
+
+impl CodeTemplate {
+    fn signature_only(signature: &str) -> String {
+        format!("{}\n    unimplemented!()\n}}", signature.trim_end())
+    }
+
+    fn render_outline(&self) -> String {
+        self.signatures.clone()
+    }
+
+    fn render_draft(&self) -> String {
+        self.body.clone()
+    }
+
+    fn render_refined(&self) -> String {
+        let mut code = self.body.clone();
+        for (marker, replacement) in &self.edge_cases {
+            code = code.replace(marker.as_str(), replacement.as_str());
+        }
+        with_docs(&code, &self.docs)
+    }
+}
+
+/// Prepends each doc comment in `docs` to the struct/enum/fn item it
+/// corresponds to, in the order those items appear in `code` (skipping
+/// `fn main`, which is never documented).
+fn with_docs(code: &str, docs: &[String]) -> String {
+    let mut docs_iter = docs.iter();
+    let mut out = String::new();
+
+    for line in code.lines() {
+        let trimmed = line.trim_start();
+        let indent = &line[..line.len() - trimmed.len()];
+        let is_documented_item = (trimmed.starts_with("fn ") && !trimmed.starts_with("fn main("))
+            || trimmed.starts_with("struct ")
+            || trimmed.starts_with("enum ");
+
+        if is_documented_item {
+            if let Some(doc) = docs_iter.next() {
+                for doc_line in doc.lines() {
+                    out.push_str(indent);
+                    out.push_str(doc_line);
+                    out.push('\n');
+                }
+            }
+        }
+
+        out.push_str(line);
+        out.push('\n');
+    }
+
+    out.trim_end_matches('\n').to_string()
+}
+
 /// The Coder agent writes Rust code based on the plan and task description.
 /// Brain: Built-in — task-type detection generates real working Rust code for 15+ task types.
 pub struct CoderAgent;
@@ -13,23 +87,25 @@ impl CoderAgent {
         println!("\x1b[1;34m[CODER]\x1b[0m \x1b[2m· Brain: Built-in (Coding)\x1b[0m");
         println!("\x1b[1;34m[CODER]\x1b[0m Generating code for: \"{}\"", task_description);
 
+        let template = self.template_for(task_description);
+
         // Pass 1: Generate outline
         ThinkingTimer::new(ProcessingStage::CodeOutline, 20).start();
-        let outline = self.generate_outline(task_description);
+        let outline = self.generate_outline(&template);
         println!("\x1b[1;34m[CODER]\x1b[0m Pass 1 - Outline created:");
         println!("\x1b[90m{}\x1b[0m", &outline[..outline.len().min(200)]);
         println!("\x1b[90m  ... (outline complete)\x1b[0m");
 
         // Pass 2: Generate draft
         ThinkingTimer::new(ProcessingStage::CodeDraft, 45).start();
-        let draft = self.generate_draft(&outline, task_description);
+        let draft = self.generate_draft(&template);
         println!("\x1b[1;34m[CODER]\x1b[0m Pass 2 - Draft implementation created:");
         println!("\x1b[90m{}\x1b[0m", &draft[..draft.len().min(200)]);
         println!("\x1b[90m  ... (draft complete)\x1b[0m");
 
         // Pass 3: Refine code
         ThinkingTimer::new(ProcessingStage::CodeRefinement, 30).start();
-        let code = self.refine_code(&draft, task_description);
+        let code = self.refine_code(&template);
         println!("\x1b[1;34m[CODER]\x1b[0m Pass 3 - Code refined and finalized:");
         println!("\x1b[90m{}\x1b[0m", code);
         println!("\x1b[1;34m[CODER]\x1b[0m Handing off to Reviewer.");
@@ -41,34 +117,31 @@ impl CoderAgent {
         }
     }
 
-    fn generate_outline(&self, description: &str) -> String {
-        // For outline, just return the full code structure with function signatures
-        // In a real implementation, this would be a skeleton with unimplemented!()
-        // For simplicity, we'll just return the same as generate_code for now
-        self.generate_code(description)
+    /// Pass 1: signatures only, `unimplemented!()` bodies, no `main`.
+    fn generate_outline(&self, template: &CodeTemplate) -> String {
+        template.render_outline()
     }
 
-    fn generate_draft(&self, _outline: &str, description: &str) -> String {
-        // For draft, add basic implementation
-        // In a real implementation, this would have TODO comments for edge cases
-        // For simplicity, we'll use generate_code
-        self.generate_code(description)
+    /// Pass 2: working core logic with `// TODO:` markers for edge cases,
+    /// no doc comments.
+    fn generate_draft(&self, template: &CodeTemplate) -> String {
+        template.render_draft()
     }
 
-    fn refine_code(&self, _draft: &str, description: &str) -> String {
-        // For refinement, replace TODOs with edge case handling and add doc comments
-        // In a real implementation, this would enhance the draft
-        // For simplicity, we'll use generate_code which already has full implementation
-        self.generate_code(description)
+    /// Pass 3: every TODO replaced with real edge-case handling, doc
+    /// comments prepended to each item.
+    fn refine_code(&self, template: &CodeTemplate) -> String {
+        template.render_refined()
     }
 
-    fn generate_code(&self, description: &str) -> String {
+    fn template_for(&self, description: &str) -> CodeTemplate {
         let desc = description.to_lowercase();
 
         if desc.contains("sort") || desc.contains("order") {
-            r#"/// Sorts a vector of integers in ascending order using Rust's built-in sort.
-/// Time complexity: O(n log n). Space complexity: O(1) in-place.
-fn sort_numbers(mut numbers: Vec<i32>) -> Vec<i32> {
+            let signatures = CodeTemplate::signature_only(
+                "fn sort_numbers(numbers: Vec<i32>) -> Vec<i32> {",
+            );
+            let body = r#"fn sort_numbers(mut numbers: Vec<i32>) -> Vec<i32> {
     numbers.sort();
     numbers
 }
@@ -84,12 +157,22 @@ fn main() {
     println!("Empty sorted: {:?}", sort_numbers(empty));
     let single = vec![42];
     println!("Single sorted: {:?}", sort_numbers(single));
-}"#.to_string()
+}"#.to_string();
+
+            CodeTemplate {
+                signatures,
+                body,
+                edge_cases: vec![],
+                docs: vec![
+                    "/// Sorts a vector of integers in ascending order using Rust's built-in sort.\n/// Time complexity: O(n log n). Space complexity: O(1) in-place.".to_string(),
+                ],
+            }
 
         } else if desc.contains("revers") {
-            r#"/// Reverses a string and returns the result as an owned String.
-/// Works correctly with multi-byte Unicode characters.
-fn reverse_string(s: &str) -> String {
+            let signatures = CodeTemplate::signature_only(
+                "fn reverse_string(s: &str) -> String {",
+            );
+            let body = r#"fn reverse_string(s: &str) -> String {
     s.chars().rev().collect()
 }
 
@@ -98,15 +181,23 @@ fn main() {
     for s in &examples {
         println!("Original: {:?}  =>  Reversed: {:?}", s, reverse_string(s));
     }
-}"#.to_string()
+}"#.to_string();
+
+            CodeTemplate {
+                signatures,
+                body,
+                edge_cases: vec![],
+                docs: vec![
+                    "/// Reverses a string and returns the result as an owned String.\n/// Works correctly with multi-byte Unicode characters.".to_string(),
+                ],
+            }
 
         } else if desc.contains("fibonacci") || desc.contains("fib") {
-            r#"/// Returns the nth Fibonacci number using an iterative approach.
-/// Base cases: fib(0) = 0, fib(1) = 1.
-/// Time complexity: O(n). Space complexity: O(1).
-fn fibonacci(n: u64) -> u64 {
-    if n == 0 { return 0; }
-    if n == 1 { return 1; }
+            let signatures = CodeTemplate::signature_only(
+                "fn fibonacci(n: u64) -> u64 {",
+            );
+            let body = r#"fn fibonacci(n: u64) -> u64 {
+    // TODO: handle base cases n == 0 and n == 1
     let (mut a, mut b) = (0u64, 1u64);
     for _ in 2..=n {
         let next = a + b;
@@ -122,13 +213,25 @@ fn main() {
         println!("  fib({}) = {}", i, fibonacci(i));
     }
     println!("fib(20) = {}", fibonacci(20));
-}"#.to_string()
+}"#.to_string();
+
+            CodeTemplate {
+                signatures,
+                body,
+                edge_cases: vec![(
+                    "    // TODO: handle base cases n == 0 and n == 1\n".to_string(),
+                    "    if n == 0 { return 0; }\n    if n == 1 { return 1; }\n".to_string(),
+                )],
+                docs: vec![
+                    "/// Returns the nth Fibonacci number using an iterative approach.\n/// Base cases: fib(0) = 0, fib(1) = 1.\n/// Time complexity: O(n). Space complexity: O(1).".to_string(),
+                ],
+            }
 
         } else if desc.contains("factorial") {
-            r#"/// Computes n! (n factorial) iteratively using a running product.
-/// By convention, 0! = 1 (empty product).
-/// Time complexity: O(n). Space complexity: O(1).
-fn factorial(n: u64) -> u64 {
+            let signatures = CodeTemplate::signature_only(
+                "fn factorial(n: u64) -> u64 {",
+            );
+            let body = r#"fn factorial(n: u64) -> u64 {
     (1..=n).product()
 }
 
@@ -137,15 +240,23 @@ fn main() {
     for i in 0..=12 {
         println!("  {}! = {}", i, factorial(i));
     }
-}"#.to_string()
+}"#.to_string();
+
+            CodeTemplate {
+                signatures,
+                body,
+                edge_cases: vec![],
+                docs: vec![
+                    "/// Computes n! (n factorial) iteratively using a running product.\n/// By convention, 0! = 1 (empty product).\n/// Time complexity: O(n). Space complexity: O(1).".to_string(),
+                ],
+            }
 
         } else if desc.contains("prime") {
-            r#"/// Returns true if n is a prime number.
-/// Uses trial division up to sqrt(n) for efficiency.
-/// Time complexity: O(sqrt n).
-fn is_prime(n: u64) -> bool {
-    if n < 2 { return false; }
-    if n == 2 { return true; }
+            let signatures = CodeTemplate::signature_only(
+                "fn is_prime(n: u64) -> bool {",
+            );
+            let body = r#"fn is_prime(n: u64) -> bool {
+    // TODO: handle n < 2 and n == 2 as special cases
     if n % 2 == 0 { return false; }
     let mut i = 3u64;
     while i * i <= n {
@@ -164,13 +275,25 @@ fn main() {
     for &n in &[1u64, 2, 13, 97, 100] {
         println!("  is_prime({}) = {}", n, is_prime(n));
     }
-}"#.to_string()
+}"#.to_string();
+
+            CodeTemplate {
+                signatures,
+                body,
+                edge_cases: vec![(
+                    "    // TODO: handle n < 2 and n == 2 as special cases\n".to_string(),
+                    "    if n < 2 { return false; }\n    if n == 2 { return true; }\n".to_string(),
+                )],
+                docs: vec![
+                    "/// Returns true if n is a prime number.\n/// Uses trial division up to sqrt(n) for efficiency.\n/// Time complexity: O(sqrt n).".to_string(),
+                ],
+            }
 
         } else if desc.contains("search") || desc.contains("find") {
-            r#"/// Searches for a target value in a slice using linear search.
-/// Returns Some(index) if found, None otherwise.
-/// Time complexity: O(n).
-fn linear_search(data: &[i32], target: i32) -> Option<usize> {
+            let signatures = CodeTemplate::signature_only(
+                "fn linear_search(data: &[i32], target: i32) -> Option<usize> {",
+            );
+            let body = r#"fn linear_search(data: &[i32], target: i32) -> Option<usize> {
     data.iter().position(|&x| x == target)
 }
 
@@ -187,13 +310,26 @@ fn main() {
         Some(i) => println!("Found {} at index {}", missing_target, i),
         None    => println!("{} not found in data", missing_target),
     }
-}"#.to_string()
+}"#.to_string();
+
+            CodeTemplate {
+                signatures,
+                body,
+                edge_cases: vec![],
+                docs: vec![
+                    "/// Searches for a target value in a slice using linear search.\n/// Returns Some(index) if found, None otherwise.\n/// Time complexity: O(n).".to_string(),
+                ],
+            }
 
         } else if desc.contains("count") || desc.contains("frequency") {
-            r#"use std::collections::HashMap;
+            let signatures = format!(
+                "use std::collections::HashMap;\n\n{}",
+                CodeTemplate::signature_only(
+                    "fn count_frequency<'a>(items: &[&'a str]) -> HashMap<&'a str, usize> {",
+                )
+            );
+            let body = r#"use std::collections::HashMap;
 
-/// Counts how many times each element appears in the input slice.
-/// Returns a HashMap mapping each element to its occurrence count.
 fn count_frequency<'a>(items: &[&'a str]) -> HashMap<&'a str, usize> {
     let mut freq: HashMap<&str, usize> = HashMap::new();
     for &item in items {
@@ -214,16 +350,27 @@ fn main() {
         println!("  {}: {}", word, count);
     }
     println!("Unique words: {}", pairs.len());
-}"#.to_string()
+}"#.to_string();
+
+            CodeTemplate {
+                signatures,
+                body,
+                edge_cases: vec![],
+                docs: vec![
+                    "/// Counts how many times each element appears in the input slice.\n/// Returns a HashMap mapping each element to its occurrence count.".to_string(),
+                ],
+            }
 
         } else if desc.contains("filter") || desc.contains("remove") {
-            r#"/// Filters a vector, keeping only elements that satisfy the given predicate.
-/// Returns a new Vec containing only the matching elements.
-fn filter_evens(numbers: Vec<i32>) -> Vec<i32> {
+            let signatures = format!(
+                "{}\n\n{}",
+                CodeTemplate::signature_only("fn filter_evens(numbers: Vec<i32>) -> Vec<i32> {"),
+                CodeTemplate::signature_only("fn filter_non_negative(numbers: Vec<i32>) -> Vec<i32> {"),
+            );
+            let body = r#"fn filter_evens(numbers: Vec<i32>) -> Vec<i32> {
     numbers.into_iter().filter(|&x| x % 2 == 0).collect()
 }
 
-/// Filters out negative numbers, keeping only non-negative values.
 fn filter_non_negative(numbers: Vec<i32>) -> Vec<i32> {
     numbers.into_iter().filter(|&x| x >= 0).collect()
 }
@@ -236,12 +383,23 @@ fn main() {
     let mixed = vec![-3, -1, 0, 2, 5, -7, 8];
     println!("Mixed:         {:?}", mixed);
     println!("Non-negative:  {:?}", filter_non_negative(mixed));
-}"#.to_string()
+}"#.to_string();
+
+            CodeTemplate {
+                signatures,
+                body,
+                edge_cases: vec![],
+                docs: vec![
+                    "/// Filters a vector, keeping only elements that satisfy the given predicate.\n/// Returns a new Vec containing only the matching elements.".to_string(),
+                    "/// Filters out negative numbers, keeping only non-negative values.".to_string(),
+                ],
+            }
 
         } else if desc.contains("palindrome") {
-            r#"/// Returns true if the string is a palindrome (ignoring case and non-alphabetic chars).
-/// Examples: "racecar" -> true, "A man a plan a canal Panama" -> true.
-fn is_palindrome(s: &str) -> bool {
+            let signatures = CodeTemplate::signature_only(
+                "fn is_palindrome(s: &str) -> bool {",
+            );
+            let body = r#"fn is_palindrome(s: &str) -> bool {
     // Normalize: keep only alphabetic characters, lowercased
     let cleaned: String = s.chars()
         .filter(|c| c.is_alphabetic())
@@ -263,13 +421,22 @@ fn main() {
     for s in &test_cases {
         println!("is_palindrome({:?}) = {}", s, is_palindrome(s));
     }
-}"#.to_string()
+}"#.to_string();
+
+            CodeTemplate {
+                signatures,
+                body,
+                edge_cases: vec![],
+                docs: vec![
+                    "/// Returns true if the string is a palindrome (ignoring case and non-alphabetic chars).\n/// Examples: \"racecar\" -> true, \"A man a plan a canal Panama\" -> true.".to_string(),
+                ],
+            }
 
         } else if desc.contains("anagram") {
-            r#"/// Returns true if two strings are anagrams of each other.
-/// Ignores case; considers only alphabetic characters.
-/// Approach: sort both character lists and compare.
-fn is_anagram(a: &str, b: &str) -> bool {
+            let signatures = CodeTemplate::signature_only(
+                "fn is_anagram(a: &str, b: &str) -> bool {",
+            );
+            let body = r#"fn is_anagram(a: &str, b: &str) -> bool {
     let normalize = |s: &str| -> Vec<char> {
         let mut chars: Vec<char> = s.chars()
             .filter(|c| c.is_alphabetic())
@@ -292,41 +459,72 @@ fn main() {
     for (a, b) in &pairs {
         println!("is_anagram({:?}, {:?}) = {}", a, b, is_anagram(a, b));
     }
-}"#.to_string()
+}"#.to_string();
+
+            CodeTemplate {
+                signatures,
+                body,
+                edge_cases: vec![],
+                docs: vec![
+                    "/// Returns true if two strings are anagrams of each other.\n/// Ignores case; considers only alphabetic characters.\n/// Approach: sort both character lists and compare.".to_string(),
+                ],
+            }
 
         } else if desc.contains("stack") {
-            r#"/// A generic LIFO stack backed by a Vec.
-struct Stack<T> {
+            let signatures = r#"struct Stack<T> {
+    data: Vec<T>,
+}
+
+impl<T> Stack<T> {
+    fn new() -> Self {
+        unimplemented!()
+    }
+
+    fn push(&mut self, value: T) {
+        unimplemented!()
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        unimplemented!()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        unimplemented!()
+    }
+
+    fn is_empty(&self) -> bool {
+        unimplemented!()
+    }
+
+    fn size(&self) -> usize {
+        unimplemented!()
+    }
+}"#.to_string();
+            let body = r#"struct Stack<T> {
     data: Vec<T>,
 }
 
 impl<T> Stack<T> {
-    /// Creates a new empty stack.
     fn new() -> Self {
         Stack { data: Vec::new() }
     }
 
-    /// Pushes a value onto the top of the stack.
     fn push(&mut self, value: T) {
         self.data.push(value);
     }
 
-    /// Removes and returns the top value, or None if empty.
     fn pop(&mut self) -> Option<T> {
         self.data.pop()
     }
 
-    /// Returns a reference to the top value without removing it.
     fn peek(&self) -> Option<&T> {
         self.data.last()
     }
 
-    /// Returns true if the stack contains no elements.
     fn is_empty(&self) -> bool {
         self.data.is_empty()
     }
 
-    /// Returns the number of elements in the stack.
     fn size(&self) -> usize {
         self.data.len()
     }
@@ -346,43 +544,82 @@ fn main() {
         println!("Popped: {}", val);
     }
     println!("Is empty after popping all: {}", stack.is_empty());
-}"#.to_string()
+}"#.to_string();
+
+            CodeTemplate {
+                signatures,
+                body,
+                edge_cases: vec![],
+                docs: vec![
+                    "/// A generic LIFO stack backed by a Vec.".to_string(),
+                    "/// Creates a new empty stack.".to_string(),
+                    "/// Pushes a value onto the top of the stack.".to_string(),
+                    "/// Removes and returns the top value, or None if empty.".to_string(),
+                    "/// Returns a reference to the top value without removing it.".to_string(),
+                    "/// Returns true if the stack contains no elements.".to_string(),
+                    "/// Returns the number of elements in the stack.".to_string(),
+                ],
+            }
 
         } else if desc.contains("queue") {
-            r#"use std::collections::VecDeque;
+            let signatures = r#"use std::collections::VecDeque;
+
+struct Queue<T> {
+    data: VecDeque<T>,
+}
+
+impl<T> Queue<T> {
+    fn new() -> Self {
+        unimplemented!()
+    }
+
+    fn enqueue(&mut self, value: T) {
+        unimplemented!()
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        unimplemented!()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        unimplemented!()
+    }
+
+    fn is_empty(&self) -> bool {
+        unimplemented!()
+    }
+
+    fn size(&self) -> usize {
+        unimplemented!()
+    }
+}"#.to_string();
+            let body = r#"use std::collections::VecDeque;
 
-/// A generic FIFO queue backed by a VecDeque.
 struct Queue<T> {
     data: VecDeque<T>,
 }
 
 impl<T> Queue<T> {
-    /// Creates a new empty queue.
     fn new() -> Self {
         Queue { data: VecDeque::new() }
     }
 
-    /// Adds a value to the back of the queue.
     fn enqueue(&mut self, value: T) {
         self.data.push_back(value);
     }
 
-    /// Removes and returns the front value, or None if empty.
     fn dequeue(&mut self) -> Option<T> {
         self.data.pop_front()
     }
 
-    /// Returns a reference to the front value without removing it.
     fn peek(&self) -> Option<&T> {
         self.data.front()
     }
 
-    /// Returns true if the queue contains no elements.
     fn is_empty(&self) -> bool {
         self.data.is_empty()
     }
 
-    /// Returns the number of elements in the queue.
     fn size(&self) -> usize {
         self.data.len()
     }
@@ -402,13 +639,32 @@ fn main() {
         println!("Dequeued: {}", name);
     }
     println!("Is empty after dequeuing all: {}", q.is_empty());
-}"#.to_string()
+}"#.to_string();
+
+            CodeTemplate {
+                signatures,
+                body,
+                edge_cases: vec![],
+                docs: vec![
+                    "/// A generic FIFO queue backed by a VecDeque.".to_string(),
+                    "/// Creates a new empty queue.".to_string(),
+                    "/// Adds a value to the back of the queue.".to_string(),
+                    "/// Removes and returns the front value, or None if empty.".to_string(),
+                    "/// Returns a reference to the front value without removing it.".to_string(),
+                    "/// Returns true if the queue contains no elements.".to_string(),
+                    "/// Returns the number of elements in the queue.".to_string(),
+                ],
+            }
 
         } else if desc.contains("hash") || desc.contains("map") || desc.contains("dictionary") {
-            r#"use std::collections::HashMap;
+            let signatures = format!(
+                "use std::collections::HashMap;\n\n{}",
+                CodeTemplate::signature_only(
+                    "fn build_phone_book() -> HashMap<String, String> {",
+                )
+            );
+            let body = r#"use std::collections::HashMap;
 
-/// Demonstrates HashMap operations: insert, lookup, update, and remove.
-/// HashMap provides O(1) average-case operations.
 fn build_phone_book() -> HashMap<String, String> {
     let mut book: HashMap<String, String> = HashMap::new();
     book.insert("Alice".to_string(),   "555-1234".to_string());
@@ -444,24 +700,48 @@ fn main() {
     for (name, num) in entries {
         println!("  {}: {}", name, num);
     }
-}"#.to_string()
+}"#.to_string();
+
+            CodeTemplate {
+                signatures,
+                body,
+                edge_cases: vec![],
+                docs: vec![
+                    "/// Demonstrates HashMap operations: insert, lookup, update, and remove.\n/// HashMap provides O(1) average-case operations.".to_string(),
+                ],
+            }
 
         } else if desc.contains("tree") || desc.contains("bst") || desc.contains("binary") {
-            r#"/// A binary search tree node. Each node holds a value and optional child subtrees.
-struct Node {
+            let signatures = r#"struct Node {
+    value: i32,
+    left:  Option<Box<Node>>,
+    right: Option<Box<Node>>,
+}
+
+impl Node {
+    fn new(value: i32) -> Self {
+        unimplemented!()
+    }
+
+    fn insert(&mut self, value: i32) {
+        unimplemented!()
+    }
+
+    fn in_order(&self) -> Vec<i32> {
+        unimplemented!()
+    }
+}"#.to_string();
+            let body = r#"struct Node {
     value: i32,
     left:  Option<Box<Node>>,
     right: Option<Box<Node>>,
 }
 
 impl Node {
-    /// Creates a new leaf node with the given value.
     fn new(value: i32) -> Self {
         Node { value, left: None, right: None }
     }
 
-    /// Inserts a value into the BST maintaining the BST invariant:
-    /// left subtree values < node value < right subtree values.
     fn insert(&mut self, value: i32) {
         if value < self.value {
             match &mut self.left {
@@ -477,7 +757,6 @@ impl Node {
         // Duplicate values are ignored
     }
 
-    /// Returns all values in sorted order via in-order traversal (left, root, right).
     fn in_order(&self) -> Vec<i32> {
         let mut result = Vec::new();
         if let Some(left) = &self.left {
@@ -499,40 +778,72 @@ fn main() {
     }
     println!("Inserted values: {:?}", values);
     println!("In-order traversal (sorted): {:?}", root.in_order());
-}"#.to_string()
+}"#.to_string();
+
+            CodeTemplate {
+                signatures,
+                body,
+                edge_cases: vec![],
+                docs: vec![
+                    "/// A binary search tree node. Each node holds a value and optional child subtrees.".to_string(),
+                    "/// Creates a new leaf node with the given value.".to_string(),
+                    "/// Inserts a value into the BST maintaining the BST invariant:\n    /// left subtree values < node value < right subtree values.".to_string(),
+                    "/// Returns all values in sorted order via in-order traversal (left, root, right).".to_string(),
+                ],
+            }
 
         } else if desc.contains("graph") {
-            r#"use std::collections::{HashMap, HashSet, VecDeque};
+            let signatures = r#"use std::collections::{HashMap, HashSet, VecDeque};
+
+struct Graph {
+    edges: HashMap<usize, Vec<usize>>,
+}
+
+impl Graph {
+    fn new() -> Self {
+        unimplemented!()
+    }
+
+    fn add_node(&mut self, node: usize) {
+        unimplemented!()
+    }
+
+    fn add_edge(&mut self, from: usize, to: usize) {
+        unimplemented!()
+    }
+
+    fn neighbors(&self, node: usize) -> &[usize] {
+        unimplemented!()
+    }
+
+    fn bfs(&self, start: usize) -> Vec<usize> {
+        unimplemented!()
+    }
+}"#.to_string();
+            let body = r#"use std::collections::{HashMap, HashSet, VecDeque};
 
-/// An undirected graph represented as an adjacency list.
 struct Graph {
     edges: HashMap<usize, Vec<usize>>,
 }
 
 impl Graph {
-    /// Creates a new empty graph.
     fn new() -> Self {
         Graph { edges: HashMap::new() }
     }
 
-    /// Adds a node to the graph (no-op if already present).
     fn add_node(&mut self, node: usize) {
         self.edges.entry(node).or_insert_with(Vec::new);
     }
 
-    /// Adds an undirected edge between two nodes.
     fn add_edge(&mut self, from: usize, to: usize) {
         self.edges.entry(from).or_insert_with(Vec::new).push(to);
         self.edges.entry(to).or_insert_with(Vec::new).push(from);
     }
 
-    /// Returns all neighbor nodes of the given node.
     fn neighbors(&self, node: usize) -> &[usize] {
         self.edges.get(&node).map(|v| v.as_slice()).unwrap_or(&[])
     }
 
-    /// Performs a breadth-first search from the start node.
-    /// Returns nodes in the order they were visited.
     fn bfs(&self, start: usize) -> Vec<usize> {
         let mut visited: HashSet<usize> = HashSet::new();
         let mut queue: VecDeque<usize> = VecDeque::new();
@@ -564,11 +875,35 @@ fn main() {
     }
     println!("BFS from node 0: {:?}", g.bfs(0));
     println!("Neighbors of 1: {:?}", g.neighbors(1));
-}"#.to_string()
+}"#.to_string();
+
+            CodeTemplate {
+                signatures,
+                body,
+                edge_cases: vec![],
+                docs: vec![
+                    "/// An undirected graph represented as an adjacency list.".to_string(),
+                    "/// Creates a new empty graph.".to_string(),
+                    "/// Adds a node to the graph (no-op if already present).".to_string(),
+                    "/// Adds an undirected edge between two nodes.".to_string(),
+                    "/// Returns all neighbor nodes of the given node.".to_string(),
+                    "/// Performs a breadth-first search from the start node.\n    /// Returns nodes in the order they were visited.".to_string(),
+                ],
+            }
 
         } else if desc.contains("calculator") || desc.contains("calc") {
-            r#"/// Supported arithmetic operations.
-#[derive(Debug)]
+            let signatures = r#"#[derive(Debug)]
+enum Operation {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+fn calculate(a: f64, op: &Operation, b: f64) -> Result<f64, String> {
+    unimplemented!()
+}"#.to_string();
+            let body = r#"#[derive(Debug)]
 enum Operation {
     Add,
     Subtract,
@@ -576,19 +911,14 @@ enum Operation {
     Divide,
 }
 
-/// Performs a binary arithmetic operation on two f64 values.
-/// Returns Err for division by zero or unknown operations.
 fn calculate(a: f64, op: &Operation, b: f64) -> Result<f64, String> {
     match op {
         Operation::Add      => Ok(a + b),
         Operation::Subtract => Ok(a - b),
         Operation::Multiply => Ok(a * b),
         Operation::Divide   => {
-            if b == 0.0 {
-                Err("Division by zero is undefined".to_string())
-            } else {
-                Ok(a / b)
-            }
+            // TODO: handle division by zero
+            Ok(a / b)
         }
     }
 }
@@ -607,11 +937,51 @@ fn main() {
             Err(e)     => println!("{} {:?} {} => Error: {}", a, op, b, e),
         }
     }
-}"#.to_string()
+}"#.to_string();
+
+            CodeTemplate {
+                signatures,
+                body,
+                edge_cases: vec![(
+                    "            // TODO: handle division by zero\n            Ok(a / b)\n".to_string(),
+                    "            if b == 0.0 {\n                Err(\"Division by zero is undefined\".to_string())\n            } else {\n                Ok(a / b)\n            }\n".to_string(),
+                )],
+                docs: vec![
+                    "/// Supported arithmetic operations.".to_string(),
+                    "/// Performs a binary arithmetic operation on two f64 values.\n/// Returns Err for division by zero or unknown operations.".to_string(),
+                ],
+            }
 
         } else if desc.contains("matrix") {
-            r#"/// A 2D matrix of f64 values with configurable dimensions.
-#[derive(Debug, Clone)]
+            let signatures = r#"#[derive(Debug, Clone)]
+struct Matrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<Vec<f64>>,
+}
+
+impl Matrix {
+    fn new(rows: usize, cols: usize) -> Self {
+        unimplemented!()
+    }
+
+    fn get(&self, row: usize, col: usize) -> f64 {
+        unimplemented!()
+    }
+
+    fn set(&mut self, row: usize, col: usize, val: f64) {
+        unimplemented!()
+    }
+
+    fn add(&self, other: &Matrix) -> Result<Matrix, String> {
+        unimplemented!()
+    }
+
+    fn print(&self) {
+        unimplemented!()
+    }
+}"#.to_string();
+            let body = r#"#[derive(Debug, Clone)]
 struct Matrix {
     rows: usize,
     cols: usize,
@@ -619,29 +989,20 @@ struct Matrix {
 }
 
 impl Matrix {
-    /// Creates a new zero-initialized matrix of the given dimensions.
     fn new(rows: usize, cols: usize) -> Self {
         Matrix { rows, cols, data: vec![vec![0.0; cols]; rows] }
     }
 
-    /// Returns the value at position (row, col).
     fn get(&self, row: usize, col: usize) -> f64 {
         self.data[row][col]
     }
 
-    /// Sets the value at position (row, col).
     fn set(&mut self, row: usize, col: usize, val: f64) {
         self.data[row][col] = val;
     }
 
-    /// Adds two matrices element-wise. Dimensions must match.
     fn add(&self, other: &Matrix) -> Result<Matrix, String> {
-        if self.rows != other.rows || self.cols != other.cols {
-            return Err(format!(
-                "Dimension mismatch: {}x{} vs {}x{}",
-                self.rows, self.cols, other.rows, other.cols
-            ));
-        }
+        // TODO: handle dimension mismatch between self and other
         let mut result = Matrix::new(self.rows, self.cols);
         for r in 0..self.rows {
             for c in 0..self.cols {
@@ -651,7 +1012,6 @@ impl Matrix {
         Ok(result)
     }
 
-    /// Prints the matrix in a readable grid format.
     fn print(&self) {
         for row in &self.data {
             let formatted: Vec<String> = row.iter().map(|x| format!("{:6.1}", x)).collect();
@@ -678,7 +1038,24 @@ fn main() {
         Ok(sum) => { println!("A + B:"); sum.print(); }
         Err(e)  => println!("Error: {}", e),
     }
-}"#.to_string()
+}"#.to_string();
+
+            CodeTemplate {
+                signatures,
+                body,
+                edge_cases: vec![(
+                    "        // TODO: handle dimension mismatch between self and other\n".to_string(),
+                    "        if self.rows != other.rows || self.cols != other.cols {\n            return Err(format!(\n                \"Dimension mismatch: {}x{} vs {}x{}\",\n                self.rows, self.cols, other.rows, other.cols\n            ));\n        }\n".to_string(),
+                )],
+                docs: vec![
+                    "/// A 2D matrix of f64 values with configurable dimensions.".to_string(),
+                    "/// Creates a new zero-initialized matrix of the given dimensions.".to_string(),
+                    "/// Returns the value at position (row, col).".to_string(),
+                    "/// Sets the value at position (row, col).".to_string(),
+                    "/// Adds two matrices element-wise. Dimensions must match.".to_string(),
+                    "/// Prints the matrix in a readable grid format.".to_string(),
+                ],
+            }
 
         } else {
             // For unknown tasks: generate a sensible stub with a real function signature
@@ -691,12 +1068,12 @@ fn main() {
                 .join("_");
             let func_name = if func_name.is_empty() { "run_task".to_string() } else { func_name };
 
-            format!(
-                r#"/// Implements: {description}
-/// Processes an input string and returns a result string.
-fn {func_name}(input: &str) -> String {{
+            let signatures = CodeTemplate::signature_only(
+                &format!("fn {func_name}(input: &str) -> String {{"),
+            );
+            let body = format!(
+                r#"fn {func_name}(input: &str) -> String {{
     // Core logic for: {description}
-    // Process the input and produce a meaningful result
     let words: Vec<&str> = input.split_whitespace().collect();
     format!("Processed {{}} word(s): {{}}", words.len(), input)
 }}
@@ -710,7 +1087,16 @@ fn main() {{
         println!();
     }}
 }}"#
-            )
+            );
+
+            CodeTemplate {
+                signatures,
+                body,
+                edge_cases: vec![],
+                docs: vec![
+                    format!("/// Implements: {description}\n/// Processes an input string and returns a result string."),
+                ],
+            }
         }
     }
 }
@@ -1,11 +1,37 @@
 use crate::messages::{FinalPayload, ReviewPayload};
+use syn::visit_mut::{self, VisitMut};
+use syn::{parse_quote, Expr, ExprMethodCall, FnArg, ItemFn, Pat, Stmt};
 
 /// The Debugger agent fixes issues identified by the Reviewer.
-/// Brain: Built-in — applies automatic fix patterns for each known issue type.
-pub struct DebuggerAgent;
+/// Brain: Built-in — runs a registry of pluggable `FixRule`s over each issue.
+pub struct DebuggerAgent {
+    rules: Vec<Box<dyn FixRule>>,
+}
 
 impl DebuggerAgent {
-    pub fn new() -> Self { DebuggerAgent }
+    pub fn new() -> Self {
+        let mut rules: Vec<Box<dyn FixRule>> = vec![
+            Box::new(CommentGuidanceRule),
+            Box::new(MagicNumberRule),
+            Box::new(LongFunctionRule),
+            Box::new(UnnecessaryMutRule),
+            Box::new(MissingMainRule),
+            Box::new(ExcessUnwrapRule),
+            Box::new(TodoUnimplementedRule),
+            Box::new(InvariantRule),
+        ];
+        rules.sort_by_key(|rule| rule.priority());
+        DebuggerAgent { rules }
+    }
+
+    /// Registers an extra fix rule (e.g. a project-specific one) on top of
+    /// the built-in set, without touching core code. Rules are re-sorted by
+    /// `priority()` so the new rule still runs in the right phase.
+    pub fn with_rule(mut self, rule: Box<dyn FixRule>) -> Self {
+        self.rules.push(rule);
+        self.rules.sort_by_key(|rule| rule.priority());
+        self
+    }
 
     pub fn process(&self, review: ReviewPayload) -> FinalPayload {
         println!("\n\x1b[1;31m[DEBUGGER]\x1b[0m \x1b[2m· Brain: Built-in (Debugging)\x1b[0m");
@@ -19,7 +45,7 @@ impl DebuggerAgent {
             );
         }
 
-        let (fixed_code, summary) = self.fix(&review);
+        let (fixed_code, summary, fixes_applied) = self.fix(&review);
 
         println!("\x1b[1;31m[DEBUGGER]\x1b[0m {}", summary);
         println!("\x1b[1;31m[DEBUGGER]\x1b[0m Handing final code to Coordinator.");
@@ -28,146 +54,633 @@ impl DebuggerAgent {
             task_id: review.task_id,
             code: fixed_code,
             summary,
+            fixes_applied,
         }
     }
 
-    fn fix(&self, review: &ReviewPayload) -> (String, String) {
+    /// Runs every registered rule (in priority order) whose `matches` fires
+    /// for at least one reported issue, collecting the description each rule
+    /// returns when it actually changes the code.
+    fn fix(&self, review: &ReviewPayload) -> (String, String, Vec<String>) {
         if review.approved {
             return (
                 review.code.clone(),
                 "Code passed review — no changes needed.".to_string(),
+                Vec::new(),
             );
         }
 
+        let issue_lowers: Vec<String> = review.issues.iter().map(|s| s.to_lowercase()).collect();
+        let ctx = FixContext {
+            parse_ok: syn::parse_file(&review.code).is_ok(),
+        };
         let mut code = review.code.clone();
         let mut fixes_applied: Vec<String> = Vec::new();
 
-        for issue in &review.issues {
-            let issue_lower = issue.to_lowercase();
-
-            // Fix 1: Missing fn main()
-            if issue_lower.contains("fn main()") && !code.contains("fn main()") {
-                code.push_str(
-                    "\n\nfn main() {\n    // Entry point added by Debugger\n    println!(\"Program complete.\");\n}",
-                );
-                fixes_applied.push("added missing fn main() entry point".to_string());
+        for rule in &self.rules {
+            let should_fire = issue_lowers.iter().any(|issue| rule.matches(issue));
+            if !should_fire {
+                continue;
             }
-
-            // Fix 2: No comments
-            if issue_lower.contains("no comments") && !code.contains("//") {
-                // Add a module-level doc comment at the top
-                code = format!(
-                    "// This module implements the requested functionality.\n// Review each function for details.\n\n{}",
-                    code
-                );
-                fixes_applied.push("added top-level inline comments".to_string());
+            if let Some(description) = rule.apply(&mut code, &ctx) {
+                fixes_applied.push(description);
             }
+        }
 
-            // Fix 3: Excessive .unwrap() calls — replace with safer match-based alternatives
-            if issue_lower.contains("unwrap()") {
-                let unwrap_count_before = code.matches(".unwrap()").count();
-                if unwrap_count_before > 3 {
-                    // Replace all .unwrap() beyond the third with expect() for clarity
-                    // We cannot safely rewrite all contexts, so we annotate the first excess occurrence
-                    code = Self::reduce_unwraps(&code);
-                    let unwrap_count_after = code.matches(".unwrap()").count();
-                    if unwrap_count_after < unwrap_count_before {
-                        fixes_applied.push(format!(
-                            "replaced {} excess .unwrap() calls with .expect() for better diagnostics",
-                            unwrap_count_before - unwrap_count_after
-                        ));
-                    }
-                }
-            }
+        let summary = if fixes_applied.is_empty() {
+            format!(
+                "Reviewed {} issue(s) — no automatic fixes could be applied; manual review recommended.",
+                review.issues.len()
+            )
+        } else {
+            format!("Applied {} fix(es): {}", fixes_applied.len(), fixes_applied.join("; "))
+        };
 
-            // Fix 4: todo!() / unimplemented!() placeholders
-            if issue_lower.contains("todo") || issue_lower.contains("unimplemented") {
-                let had_todo  = code.contains("todo!()");
-                let had_unimpl = code.contains("unimplemented!()");
+        (code, summary, fixes_applied)
+    }
+}
 
-                if had_todo {
-                    code = code.replace("todo!()", "Default::default() /* fixed: was todo!() */");
-                    fixes_applied.push("replaced todo!() with Default::default()".to_string());
-                }
-                if had_unimpl {
-                    code = code.replace(
-                        "unimplemented!()",
-                        "Default::default() /* fixed: was unimplemented!() */",
-                    );
-                    fixes_applied.push(
-                        "replaced unimplemented!() with Default::default()".to_string(),
-                    );
-                }
-            }
+/// Shared context every `FixRule` sees when deciding how to apply itself.
+/// Currently just records whether the original code parsed as valid Rust, so
+/// structural rules know whether they can use a real AST rewrite or must fall
+/// back to a string heuristic for a broken draft.
+pub struct FixContext {
+    pub parse_ok: bool,
+}
+
+/// A single pluggable Debugger fix. `DebuggerAgent` owns a `Vec<Box<dyn
+/// FixRule>>`, tries each rule against every reported issue, and applies the
+/// ones that match — so adding a project-specific fix is a new `FixRule`
+/// impl passed to `DebuggerAgent::with_rule`, not a new branch in a growing
+/// method.
+pub trait FixRule {
+    /// Whether this rule should fire for the given (lowercased) issue text.
+    fn matches(&self, issue: &str) -> bool;
+    /// Applies the fix in place, returning a human-readable description of
+    /// what changed, or `None` if there was nothing left to do (e.g. the
+    /// code already satisfies the rule, or its shape doesn't match).
+    fn apply(&self, code: &mut String, ctx: &FixContext) -> Option<String>;
+    /// Rules with a lower priority run first. Normalization passes (adding
+    /// comments, constant-naming guidance) default to a negative priority so
+    /// they run before the structural AST rewrites below them.
+    fn priority(&self) -> i32 {
+        0
+    }
+}
+
+/// Fix: no comments in the code — prepends a module-level note.
+struct CommentGuidanceRule;
+impl FixRule for CommentGuidanceRule {
+    fn matches(&self, issue: &str) -> bool {
+        issue.contains("no comments")
+    }
+
+    fn apply(&self, code: &mut String, _ctx: &FixContext) -> Option<String> {
+        if code.contains("//") {
+            return None;
+        }
+        *code = format!(
+            "// This module implements the requested functionality.\n// Review each function for details.\n\n{}",
+            code
+        );
+        Some("added top-level inline comments".to_string())
+    }
+
+    fn priority(&self) -> i32 {
+        -10
+    }
+}
+
+/// Fix: magic numbers — suggests named constants via a guidance comment.
+struct MagicNumberRule;
+impl FixRule for MagicNumberRule {
+    fn matches(&self, issue: &str) -> bool {
+        issue.contains("magic number")
+    }
 
-            // Fix 5: Magic numbers — add a comment block suggesting constants
-            if issue_lower.contains("magic number") {
-                let already_has_const = code.contains("const ");
-                if !already_has_const {
-                    code = format!(
-                        "// Consider defining named constants for numeric literals, e.g.:\n// const MAX_SIZE: usize = 100;\n\n{}",
-                        code
-                    );
-                    fixes_applied
-                        .push("added guidance comment about named constants".to_string());
+    fn apply(&self, code: &mut String, _ctx: &FixContext) -> Option<String> {
+        if code.contains("const ") {
+            return None;
+        }
+        *code = format!(
+            "// Consider defining named constants for numeric literals, e.g.:\n// const MAX_SIZE: usize = 100;\n\n{}",
+            code
+        );
+        Some("added guidance comment about named constants".to_string())
+    }
+
+    fn priority(&self) -> i32 {
+        -10
+    }
+}
+
+/// Fix: function spans too many lines — adds a refactoring note.
+struct LongFunctionRule;
+impl FixRule for LongFunctionRule {
+    fn matches(&self, issue: &str) -> bool {
+        // Parenthesized explicitly: matches either phrasing of "this is a
+        // long function", but only when the issue is actually about a
+        // function (not e.g. an unrelated line-count remark).
+        (issue.contains("function spans") || issue.contains("lines")) && issue.contains("function")
+    }
+
+    fn apply(&self, code: &mut String, _ctx: &FixContext) -> Option<String> {
+        if code.contains("// NOTE: consider refactoring") {
+            return None;
+        }
+        code.push_str(
+            "\n\n// NOTE: consider refactoring long functions into smaller helpers for readability.",
+        );
+        Some("added refactoring suggestion for long function".to_string())
+    }
+
+    fn priority(&self) -> i32 {
+        -10
+    }
+}
+
+/// Fix: unnecessary `let mut` — adds a lint note.
+struct UnnecessaryMutRule;
+impl FixRule for UnnecessaryMutRule {
+    fn matches(&self, issue: &str) -> bool {
+        issue.contains("let mut") && issue.contains("prefer")
+    }
+
+    fn apply(&self, code: &mut String, _ctx: &FixContext) -> Option<String> {
+        if code.contains("// LINT:") {
+            return None;
+        }
+        *code = format!(
+            "// LINT: review 'let mut' usages — prefer immutable 'let' where reassignment is unnecessary.\n\n{}",
+            code
+        );
+        Some("added lint note about unnecessary mut declarations".to_string())
+    }
+
+    fn priority(&self) -> i32 {
+        -10
+    }
+}
+
+/// Fix: missing `fn main()` entry point. Uses a real AST check/insert when
+/// the code parses; falls back to a plain string append otherwise.
+struct MissingMainRule;
+impl FixRule for MissingMainRule {
+    fn matches(&self, issue: &str) -> bool {
+        issue.contains("fn main()")
+    }
+
+    fn apply(&self, code: &mut String, ctx: &FixContext) -> Option<String> {
+        if code.contains("fn main()") {
+            return None;
+        }
+
+        if ctx.parse_ok {
+            let mut file = syn::parse_file(code).ok()?;
+            let has_main = file
+                .items
+                .iter()
+                .any(|item| matches!(item, syn::Item::Fn(f) if f.sig.ident == "main"));
+            if has_main {
+                return None;
+            }
+            let main_fn: syn::Item = parse_quote! {
+                fn main() {
+                    // Entry point added by Debugger
+                    println!("Program complete.");
                 }
+            };
+            file.items.push(main_fn);
+            *code = prettyplease::unparse(&file);
+            Some("added missing fn main() entry point (AST)".to_string())
+        } else {
+            code.push_str(
+                "\n\nfn main() {\n    // Entry point added by Debugger\n    println!(\"Program complete.\");\n}",
+            );
+            Some("added missing fn main() entry point".to_string())
+        }
+    }
+}
+
+/// Fix: excessive `.unwrap()` calls beyond the first three — replaces the
+/// rest with `.expect(...)` for a clearer panic message. Uses a real AST
+/// walk when the code parses; falls back to a string pass otherwise.
+struct ExcessUnwrapRule;
+impl FixRule for ExcessUnwrapRule {
+    fn matches(&self, issue: &str) -> bool {
+        issue.contains("unwrap()")
+    }
+
+    fn apply(&self, code: &mut String, ctx: &FixContext) -> Option<String> {
+        if ctx.parse_ok {
+            let mut file = syn::parse_file(code).ok()?;
+            let mut visitor = FixVisitor {
+                fix_unwrap: true,
+                fix_todo: false,
+                unwrap_seen: 0,
+                unwraps_fixed: 0,
+                todo_fixed: 0,
+                unimplemented_fixed: 0,
+            };
+            visitor.visit_file_mut(&mut file);
+            if visitor.unwraps_fixed == 0 {
+                return None;
             }
+            *code = prettyplease::unparse(&file);
+            Some(format!(
+                "replaced {} excess .unwrap() call(s) with .expect() for better diagnostics (AST)",
+                visitor.unwraps_fixed
+            ))
+        } else {
+            let unwrap_count_before = code.matches(".unwrap()").count();
+            if unwrap_count_before <= 3 {
+                return None;
+            }
+            *code = reduce_unwraps(code);
+            let unwrap_count_after = code.matches(".unwrap()").count();
+            if unwrap_count_after >= unwrap_count_before {
+                return None;
+            }
+            Some(format!(
+                "replaced {} excess .unwrap() calls with .expect() for better diagnostics",
+                unwrap_count_before - unwrap_count_after
+            ))
+        }
+    }
+}
 
-            // Fix 6: Function too long — add a refactoring note
-            if issue_lower.contains("function spans") || issue_lower.contains("lines") && issue_lower.contains("function") {
-                if !code.contains("// NOTE: consider refactoring") {
-                    code.push_str(
-                        "\n\n// NOTE: consider refactoring long functions into smaller helpers for readability.",
-                    );
-                    fixes_applied
-                        .push("added refactoring suggestion for long function".to_string());
-                }
+/// Fix: `todo!()` / `unimplemented!()` placeholders — replaces each with
+/// `Default::default()`. Uses a real AST walk when the code parses; falls
+/// back to a string replace otherwise.
+struct TodoUnimplementedRule;
+impl FixRule for TodoUnimplementedRule {
+    fn matches(&self, issue: &str) -> bool {
+        issue.contains("todo") || issue.contains("unimplemented")
+    }
+
+    fn apply(&self, code: &mut String, ctx: &FixContext) -> Option<String> {
+        if ctx.parse_ok {
+            let mut file = syn::parse_file(code).ok()?;
+            let mut visitor = FixVisitor {
+                fix_unwrap: false,
+                fix_todo: true,
+                unwrap_seen: 0,
+                unwraps_fixed: 0,
+                todo_fixed: 0,
+                unimplemented_fixed: 0,
+            };
+            visitor.visit_file_mut(&mut file);
+            if visitor.todo_fixed == 0 && visitor.unimplemented_fixed == 0 {
+                return None;
+            }
+            *code = prettyplease::unparse(&file);
+            let mut parts = Vec::new();
+            if visitor.todo_fixed > 0 {
+                parts.push(format!(
+                    "replaced {} todo!() call(s) with Default::default() (AST)",
+                    visitor.todo_fixed
+                ));
+            }
+            if visitor.unimplemented_fixed > 0 {
+                parts.push(format!(
+                    "replaced {} unimplemented!() call(s) with Default::default() (AST)",
+                    visitor.unimplemented_fixed
+                ));
+            }
+            Some(parts.join("; "))
+        } else {
+            let had_todo = code.contains("todo!()");
+            let had_unimpl = code.contains("unimplemented!()");
+            if !had_todo && !had_unimpl {
+                return None;
+            }
+            let mut parts = Vec::new();
+            if had_todo {
+                *code = code.replace("todo!()", "Default::default() /* fixed: was todo!() */");
+                parts.push("replaced todo!() with Default::default()".to_string());
+            }
+            if had_unimpl {
+                *code = code.replace(
+                    "unimplemented!()",
+                    "Default::default() /* fixed: was unimplemented!() */",
+                );
+                parts.push("replaced unimplemented!() with Default::default()".to_string());
             }
+            Some(parts.join("; "))
+        }
+    }
+}
 
-            // Fix 7: Unnecessary mut — add linting note
-            if issue_lower.contains("let mut") && issue_lower.contains("prefer") {
-                if !code.contains("// LINT:") {
-                    code = format!(
-                        "// LINT: review 'let mut' usages — prefer immutable 'let' where reassignment is unnecessary.\n\n{}",
-                        code
-                    );
-                    fixes_applied
-                        .push("added lint note about unnecessary mut declarations".to_string());
-                }
+/// Fix: missing loop invariant / correctness self-checks — wraps the
+/// recognized algorithmic helpers with `debug_assert!`s (see
+/// `inject_invariants`). Only fires when the code parses; there's no string
+/// heuristic equivalent for an AST-shaped rewrite like this one.
+struct InvariantRule;
+impl FixRule for InvariantRule {
+    fn matches(&self, issue: &str) -> bool {
+        issue.contains("invariant") || issue.contains("correctness")
+    }
+
+    fn apply(&self, code: &mut String, ctx: &FixContext) -> Option<String> {
+        if !ctx.parse_ok {
+            return None;
+        }
+        let mut file = syn::parse_file(code).ok()?;
+        let applied = inject_invariants(&mut file);
+        if applied.is_empty() {
+            return None;
+        }
+        *code = prettyplease::unparse(&file);
+        Some(applied.join("; "))
+    }
+
+    fn priority(&self) -> i32 {
+        // Runs after the unwrap/todo AST rewrites above so it instruments
+        // the already-cleaned-up function bodies.
+        10
+    }
+}
+
+/// Wraps the loop (or tail expression) of each recognized algorithmic helper
+/// with `debug_assert!`-based invariant/postcondition checks, so the
+/// generated code self-verifies its own correctness at runtime. Each helper
+/// below targets the exact shape the Coder emits for that task type; a
+/// function whose shape doesn't match (e.g. already hand-edited) is simply
+/// left untouched.
+fn inject_invariants(file: &mut syn::File) -> Vec<String> {
+    let mut applied = Vec::new();
+    for item in &mut file.items {
+        if let syn::Item::Fn(func) = item {
+            let name = func.sig.ident.to_string();
+            let did_inject = match name.as_str() {
+                "sort_numbers" => inject_sort_invariant(func),
+                "linear_search" => inject_search_postcondition(func),
+                "is_prime" => inject_prime_invariant(func),
+                "fibonacci" => inject_fibonacci_invariant(func),
+                "factorial" => inject_factorial_postcondition(func),
+                _ => false,
+            };
+            if did_inject {
+                applied.push(format!(
+                    "injected loop invariant/postcondition debug_assert!s into `{}` (AST)",
+                    name
+                ));
             }
         }
+    }
+    applied
+}
 
-        let summary = if fixes_applied.is_empty() {
-            format!(
-                "Reviewed {} issue(s) — no automatic fixes could be applied; manual review recommended.",
-                review.issues.len()
-            )
-        } else {
-            format!("Applied {} fix(es): {}", fixes_applied.len(), fixes_applied.join("; "))
-        };
+/// First parameter's binding identifier, e.g. `numbers` in `mut numbers: Vec<i32>`.
+fn first_param_ident(func: &ItemFn) -> Option<syn::Ident> {
+    match func.sig.inputs.first()? {
+        FnArg::Typed(pat_type) => match &*pat_type.pat {
+            Pat::Ident(pat_ident) => Some(pat_ident.ident.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// `sort_numbers`: after the in-place `<param>.sort()` call, asserts the result
+/// is sorted and a permutation of a snapshot taken just before the sort.
+fn inject_sort_invariant(func: &mut ItemFn) -> bool {
+    let Some(param) = first_param_ident(func) else { return false };
+
+    let stmts = &mut func.block.stmts;
+    let sort_idx = stmts.iter().position(|stmt| {
+        matches!(stmt, Stmt::Expr(Expr::MethodCall(call), _)
+            if call.method == "sort"
+                && matches!(&*call.receiver, Expr::Path(p) if p.path.is_ident(&param)))
+    });
+    let Some(idx) = sort_idx else { return false };
 
-        (code, summary)
+    let snapshot: Stmt = parse_quote! { let __invariant_original = #param.clone(); };
+    let sorted_check: Stmt = parse_quote! {
+        debug_assert!(
+            #param.windows(2).all(|w| w[0] <= w[1]),
+            "postcondition: result must be sorted"
+        );
+    };
+    let permutation_check: Stmt = parse_quote! {
+        {
+            let mut __invariant_reference = __invariant_original.clone();
+            __invariant_reference.sort();
+            debug_assert!(
+                #param == __invariant_reference,
+                "postcondition: result must be a permutation of the input"
+            );
+        }
+    };
+
+    stmts.insert(idx, snapshot);
+    stmts.insert(idx + 2, sorted_check);
+    stmts.insert(idx + 3, permutation_check);
+    true
+}
+
+/// `linear_search`: binds the tail search expression to a name and asserts
+/// the documented postcondition before returning it.
+fn inject_search_postcondition(func: &mut ItemFn) -> bool {
+    let params: Vec<syn::Ident> = func
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(pat_type) => match &*pat_type.pat {
+                Pat::Ident(pat_ident) => Some(pat_ident.ident.clone()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    let [data, target] = params.as_slice() else { return false };
+
+    let stmts = &mut func.block.stmts;
+    if !matches!(stmts.last(), Some(Stmt::Expr(_, None))) {
+        return false;
+    }
+    let tail_expr = match stmts.pop().unwrap() {
+        Stmt::Expr(expr, None) => expr,
+        _ => unreachable!(),
+    };
+
+    let binding: Stmt = parse_quote! { let __invariant_result = #tail_expr; };
+    let check: Stmt = parse_quote! {
+        debug_assert!(
+            __invariant_result.map_or(
+                #data.iter().all(|&x| x != #target),
+                |i| #data[i] == #target
+            ),
+            "postcondition: result must reflect presence/absence of the target"
+        );
+    };
+    let ret: Stmt = parse_quote! { __invariant_result };
+
+    stmts.push(binding);
+    stmts.push(check);
+    stmts.push(ret);
+    true
+}
+
+/// `is_prime`: asserts inside the trial-division `while` loop that no odd
+/// divisor smaller than the current candidate has divided `n`, then repeats
+/// the check as a postcondition right after the loop.
+fn inject_prime_invariant(func: &mut ItemFn) -> bool {
+    let Some(n) = first_param_ident(func) else { return false };
+
+    let stmts = &mut func.block.stmts;
+    let while_idx = stmts
+        .iter()
+        .position(|stmt| matches!(stmt, Stmt::Expr(Expr::While(_), _)));
+    let Some(idx) = while_idx else { return false };
+
+    let invariant: Stmt = parse_quote! {
+        debug_assert!(
+            (3..i).step_by(2).all(|j| #n % j != 0),
+            "loop invariant: no odd divisor smaller than i has divided n"
+        );
+    };
+    match &mut stmts[idx] {
+        Stmt::Expr(Expr::While(while_expr), _) => while_expr.body.stmts.push(invariant),
+        _ => return false,
+    }
+
+    let postcondition: Stmt = parse_quote! {
+        debug_assert!(
+            (3..i).step_by(2).all(|j| #n % j != 0),
+            "postcondition: n has no divisor up to sqrt(n)"
+        );
+    };
+    stmts.insert(idx + 1, postcondition);
+    true
+}
+
+/// `fibonacci`: asserts inside the accumulator `for` loop that the running
+/// pair stays ordered (`b >= a`), then repeats the check as a postcondition.
+fn inject_fibonacci_invariant(func: &mut ItemFn) -> bool {
+    let stmts = &mut func.block.stmts;
+    let for_idx = stmts
+        .iter()
+        .position(|stmt| matches!(stmt, Stmt::Expr(Expr::ForLoop(_), _)));
+    let Some(idx) = for_idx else { return false };
+
+    let invariant: Stmt = parse_quote! {
+        debug_assert!(b >= a, "loop invariant: b is the next Fibonacci term and b >= a");
+    };
+    match &mut stmts[idx] {
+        Stmt::Expr(Expr::ForLoop(for_expr), _) => for_expr.body.stmts.push(invariant),
+        _ => return false,
     }
 
-    /// Replaces .unwrap() calls beyond the third occurrence with .expect("<description>")
-    /// to provide better diagnostic messages while preserving the first few usages.
-    fn reduce_unwraps(code: &str) -> String {
-        let mut result = String::with_capacity(code.len());
-        let mut remaining = code;
-        let mut count = 0usize;
-        let needle = ".unwrap()";
+    let postcondition: Stmt = parse_quote! {
+        debug_assert!(a <= b, "postcondition: final pair keeps Fibonacci ordering");
+    };
+    stmts.insert(idx + 1, postcondition);
+    true
+}
+
+/// `factorial`: binds the tail product expression to a name and asserts the
+/// documented postcondition (`n! >= n` for `n >= 1`) before returning it.
+fn inject_factorial_postcondition(func: &mut ItemFn) -> bool {
+    let Some(n) = first_param_ident(func) else { return false };
+
+    let stmts = &mut func.block.stmts;
+    if !matches!(stmts.last(), Some(Stmt::Expr(_, None))) {
+        return false;
+    }
+    let tail_expr = match stmts.pop().unwrap() {
+        Stmt::Expr(expr, None) => expr,
+        _ => unreachable!(),
+    };
 
-        while let Some(pos) = remaining.find(needle) {
-            result.push_str(&remaining[..pos]);
-            count += 1;
-            if count > 3 {
-                result.push_str(".expect(\"value should be present\")");
-            } else {
-                result.push_str(needle);
+    let binding: Stmt = parse_quote! { let __invariant_result = #tail_expr; };
+    let check: Stmt = parse_quote! {
+        debug_assert!(
+            #n == 0 || __invariant_result >= #n,
+            "postcondition: n! must be >= n for n >= 1"
+        );
+    };
+    let ret: Stmt = parse_quote! { __invariant_result };
+
+    stmts.push(binding);
+    stmts.push(check);
+    stmts.push(ret);
+    true
+}
+
+/// Replaces .unwrap() calls beyond the third occurrence with .expect("<description>")
+/// to provide better diagnostic messages while preserving the first few usages.
+fn reduce_unwraps(code: &str) -> String {
+    let mut result = String::with_capacity(code.len());
+    let mut remaining = code;
+    let mut count = 0usize;
+    let needle = ".unwrap()";
+
+    while let Some(pos) = remaining.find(needle) {
+        result.push_str(&remaining[..pos]);
+        count += 1;
+        if count > 3 {
+            result.push_str(".expect(\"value should be present\")");
+        } else {
+            result.push_str(needle);
+        }
+        remaining = &remaining[pos + needle.len()..];
+    }
+    result.push_str(remaining);
+    result
+}
+
+/// Walks a parsed file rewriting `.unwrap()`/`todo!()`/`unimplemented!()` as
+/// structured AST transforms rather than string search/replace, so a `.unwrap()`
+/// sitting inside a string literal or a comment can never be mistaken for a real
+/// method call.
+struct FixVisitor {
+    fix_unwrap: bool,
+    fix_todo: bool,
+    /// Count of `.unwrap()` method calls visited so far (preserves the first
+    /// three, same threshold as the string-based `reduce_unwraps`).
+    unwrap_seen: usize,
+    unwraps_fixed: usize,
+    todo_fixed: usize,
+    unimplemented_fixed: usize,
+}
+
+impl VisitMut for FixVisitor {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        if self.fix_todo {
+            if let Expr::Macro(expr_macro) = expr {
+                if expr_macro.mac.path.is_ident("todo") {
+                    *expr = parse_quote!(Default::default() /* fixed: was todo!() */);
+                    self.todo_fixed += 1;
+                    return;
+                }
+                if expr_macro.mac.path.is_ident("unimplemented") {
+                    *expr = parse_quote!(Default::default() /* fixed: was unimplemented!() */);
+                    self.unimplemented_fixed += 1;
+                    return;
+                }
+            }
+        }
+
+        // Recurse into children first (e.g. the receiver of a method call) so a
+        // chained `a.b().unwrap()` has its nested nodes visited before we decide
+        // whether to rewrite this one.
+        visit_mut::visit_expr_mut(self, expr);
+
+        if self.fix_unwrap {
+            if let Expr::MethodCall(ExprMethodCall { method, args, .. }) = expr {
+                if method == "unwrap" && args.is_empty() {
+                    self.unwrap_seen += 1;
+                    if self.unwrap_seen > 3 {
+                        if let Expr::MethodCall(call) = expr {
+                            call.method = syn::Ident::new("expect", call.method.span());
+                            call.args.push(parse_quote!("value should be present"));
+                            self.unwraps_fixed += 1;
+                        }
+                    }
+                }
             }
-            remaining = &remaining[pos + needle.len()..];
         }
-        result.push_str(remaining);
-        result
     }
 }
@@ -1,4 +1,5 @@
 use crate::messages::{PlanPayload, TaskPayload};
+use crate::spec_parser::{self, Requirements};
 use crate::thinking::{ThinkingTimer, ProcessingStage};
 
 /// The Planner agent breaks a task into ordered implementation steps.
@@ -15,18 +16,19 @@ impl PlannerAgent {
         // Stage 1: Extract requirements
         ThinkingTimer::new(ProcessingStage::RequirementsExtraction, 10).start();
         let requirements = self.extract_requirements(&task.description);
+        let requirement_lines = requirements.to_display_lines();
         println!("\x1b[1;36m[PLANNER]\x1b[0m Extracted requirements:");
-        for (i, req) in requirements.iter().enumerate() {
+        for (i, req) in requirement_lines.iter().enumerate() {
             println!("\x1b[1;36m[PLANNER]\x1b[0m   {}. {}", i + 1, req);
         }
 
         // Stage 2: Generate implementation steps
         ThinkingTimer::new(ProcessingStage::Planning, 15).start();
         println!("\x1b[1;36m[PLANNER]\x1b[0m Breaking task down into steps...");
-        let mut steps = self.generate_steps(&task.description);
+        let mut steps = self.generate_steps(&task.description, &requirements);
 
         // Prepend requirements as special entries
-        let mut requirements_steps: Vec<String> = requirements
+        let mut requirements_steps: Vec<String> = requirement_lines
             .iter()
             .map(|req| format!("REQUIREMENT: {}", req))
             .collect();
@@ -40,77 +42,25 @@ impl PlannerAgent {
         PlanPayload { task_id: task.task_id, steps: requirements_steps }
     }
 
-    fn extract_requirements(&self, description: &str) -> Vec<String> {
-        let mut requirements = Vec::new();
-        let desc_lower = description.to_lowercase();
-        let words: Vec<&str> = description.split_whitespace().collect();
-
-        // Extract input requirements
-        for (i, word) in words.iter().enumerate() {
-            let word_lower = word.to_lowercase();
-            if word_lower == "takes" || word_lower == "accepts" || word_lower == "given" || word_lower == "input" {
-                if i + 1 < words.len() {
-                    let input_desc = words[i + 1..].iter().take(5).cloned().collect::<Vec<_>>().join(" ");
-                    requirements.push(format!("Input: {}", input_desc));
-                    break;
-                }
-            }
-        }
-
-        // Extract output requirements
-        for (i, word) in words.iter().enumerate() {
-            let word_lower = word.to_lowercase();
-            if word_lower == "returns" || word_lower == "produces" || word_lower == "output" {
-                if i + 1 < words.len() {
-                    let output_desc = words[i + 1..].iter().take(5).cloned().collect::<Vec<_>>().join(" ");
-                    requirements.push(format!("Output: {}", output_desc));
-                    break;
-                }
-            }
-        }
-
-        // Extract constraints
-        if desc_lower.contains("must be o(") || desc_lower.contains("complexity") {
-            requirements.push("Performance constraint specified in task".to_string());
-        }
-        if desc_lower.contains("recursion") || desc_lower.contains("recursive") {
-            requirements.push("Must use recursion".to_string());
-        }
-        if desc_lower.contains("no unwrap") || desc_lower.contains("error handling") {
-            requirements.push("Must handle errors properly without unwrap()".to_string());
-        }
-
-        // Extract edge cases
-        if desc_lower.contains("empty") {
-            requirements.push("Handle empty input".to_string());
-        }
-        if desc_lower.contains("negative") {
-            requirements.push("Handle negative numbers".to_string());
-        }
-        if desc_lower.contains("unicode") {
-            requirements.push("Support Unicode characters".to_string());
-        }
-
-        // Default requirements if none found
-        if requirements.is_empty() {
-            requirements.push("Process the input meaningfully".to_string());
-            requirements.push("Return a valid result".to_string());
-            requirements.push("Handle edge cases (empty/null inputs)".to_string());
-        }
-
-        requirements
+    /// Parses the task description against the `spec_parser` mini-grammar
+    /// instead of scanning for bare keywords and grabbing the next five
+    /// tokens, so clauses like `takes Vec<i32>` or `must be O(n log n)` are
+    /// recognized by structure rather than guessed at.
+    pub(crate) fn extract_requirements(&self, description: &str) -> Requirements {
+        spec_parser::parse_spec(description)
     }
 
-    fn generate_steps(&self, description: &str) -> Vec<String> {
+    pub(crate) fn generate_steps(&self, description: &str, reqs: &Requirements) -> Vec<String> {
         let desc = description.to_lowercase();
 
-        if desc.contains("sort") || desc.contains("order") {
+        let mut steps = if desc.contains("sort") || desc.contains("order") {
             vec![
                 "Define a function that takes a Vec<i32> as input".to_string(),
                 "Use Rust's built-in .sort() method for ascending order".to_string(),
                 "Handle edge cases: empty slice and single-element slice".to_string(),
                 "Add /// doc comments explaining the sort logic and complexity O(n log n)".to_string(),
                 "Write a main() that tests sorting with a sample vec and prints before/after".to_string(),
+                "State and check the loop invariant: after k completed iterations the last k elements are the k largest and already in sorted position; the postcondition is that the whole slice is sorted and a permutation of the input".to_string(),
             ]
         } else if desc.contains("revers") {
             vec![
@@ -127,6 +77,7 @@ impl PlannerAgent {
                 "Handle base cases explicitly: fib(0)=0, fib(1)=1".to_string(),
                 "Add /// doc comments explaining the iterative approach and O(n) complexity".to_string(),
                 "Write a main() that prints the first 10 Fibonacci numbers in a loop".to_string(),
+                "State and check the loop invariant: on every iteration the accumulator pair (a, b) holds two consecutive Fibonacci terms with b >= a".to_string(),
             ]
         } else if desc.contains("factorial") {
             vec![
@@ -135,6 +86,7 @@ impl PlannerAgent {
                 "Handle the edge case: 0! = 1 (empty product convention)".to_string(),
                 "Add /// doc comments explaining the factorial definition".to_string(),
                 "Write a main() that prints factorials for n = 0 through 12".to_string(),
+                "State and check the postcondition: the computed result must be >= n for every n >= 1".to_string(),
             ]
         } else if desc.contains("prime") {
             vec![
@@ -143,6 +95,7 @@ impl PlannerAgent {
                 "Handle edge cases: n < 2 is not prime, n == 2 is prime, skip even numbers".to_string(),
                 "Add /// doc comments explaining trial division and O(sqrt n) complexity".to_string(),
                 "Write a main() that collects and prints all prime numbers up to 50".to_string(),
+                "State and check the loop invariant: no odd divisor smaller than the current candidate has divided n evenly; the postcondition is that n has no divisor up to sqrt(n)".to_string(),
             ]
         } else if desc.contains("search") || desc.contains("find") {
             vec![
@@ -151,6 +104,7 @@ impl PlannerAgent {
                 "Return Some(index) when found, None when absent".to_string(),
                 "Add /// doc comments explaining linear search and O(n) time complexity".to_string(),
                 "Write a main() that demonstrates both found and not-found cases".to_string(),
+                "State and check the postcondition: result.map_or(data.iter().all(|&x| x != target), |i| data[i] == target)".to_string(),
             ]
         } else if desc.contains("count") || desc.contains("frequency") {
             vec![
@@ -255,6 +209,21 @@ impl PlannerAgent {
                 "Add /// doc comments explaining inputs, outputs, and the algorithm used".to_string(),
                 "Write a main() function with multiple test cases and print all results".to_string(),
             ]
+        };
+
+        // Branch on the parsed spec-grammar types/constraints instead of
+        // re-scanning the description, so an explicit `takes`/`returns`/
+        // `must be O(...)` clause refines the plan beyond the task-type guess.
+        if let Some(input_ty) = reqs.inputs.first() {
+            steps.push(format!("Parsed spec: input type is `{}`", input_ty));
+        }
+        if let Some(output_ty) = reqs.outputs.first() {
+            steps.push(format!("Parsed spec: output type is `{}`", output_ty));
         }
+        if let Some(complexity) = &reqs.complexity {
+            steps.push(format!("Parsed spec: must run in {}", complexity));
+        }
+
+        steps
     }
 }
@@ -1,17 +1,62 @@
+use crate::ai_client::{call_batch, Backend};
 use crate::messages::{CodePayload, ReviewPayload};
+use syn::visit::{self, Visit};
+
+/// Which analysis `ReviewerAgent` runs over the code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewerBrain {
+    /// The original hand-scanned-character heuristics — fast, dependency-free,
+    /// but prone to false positives on macros, raw strings, and nested blocks.
+    Heuristic,
+    /// Parses the code with `syn` and walks the real AST — see `review_code_ast`.
+    /// Falls back to `Heuristic` if the code doesn't parse as valid Rust.
+    Ast,
+}
 
 /// The Reviewer agent checks code for quality issues using static analysis rules.
 /// Brain: Built-in — applies Rust code quality checks directly with specific diagnostics.
-pub struct ReviewerAgent;
+pub struct ReviewerAgent {
+    brain: ReviewerBrain,
+    /// When set, the findings of either brain are sent to this backend as
+    /// plain chat completions for natural-language remediation suggestions
+    /// (printed, not fed back into `issues` — the Debugger still matches on
+    /// the plain diagnostic strings). No tools are offered to the model.
+    remediation_backend: Option<Backend>,
+}
 
 impl ReviewerAgent {
-    pub fn new() -> Self { ReviewerAgent }
+    pub fn new() -> Self {
+        ReviewerAgent {
+            brain: ReviewerBrain::Heuristic,
+            remediation_backend: None,
+        }
+    }
+
+    /// Switches the analysis brain (e.g. to `ReviewerBrain::Ast`).
+    pub fn with_brain(mut self, brain: ReviewerBrain) -> Self {
+        self.brain = brain;
+        self
+    }
+
+    /// Enables AI remediation suggestions from `backend` (plain chat
+    /// completions, no tool calls) once issues are found.
+    pub fn with_ai_remediation(mut self, backend: Backend) -> Self {
+        self.remediation_backend = Some(backend);
+        self
+    }
 
     pub fn process(&self, code_payload: CodePayload) -> ReviewPayload {
         println!("\n\x1b[1;35m[REVIEWER]\x1b[0m Received code for review. Analyzing...");
-        println!("\x1b[1;35m[REVIEWER]\x1b[0m \x1b[2m· Brain: Built-in (Security & Docs)\x1b[0m");
+        let brain_label = match self.brain {
+            ReviewerBrain::Heuristic => "Built-in",
+            ReviewerBrain::Ast => "AST (syn)",
+        };
+        println!("\x1b[1;35m[REVIEWER]\x1b[0m \x1b[2m· Brain: {} (Security & Docs)\x1b[0m", brain_label);
 
-        let issues = self.review_code(&code_payload.code);
+        let issues = match self.brain {
+            ReviewerBrain::Heuristic => self.review_code(&code_payload.code),
+            ReviewerBrain::Ast => self.review_code_ast(&code_payload.code),
+        };
         let approved = issues.is_empty();
 
         if approved {
@@ -22,6 +67,12 @@ impl ReviewerAgent {
                 println!("\x1b[1;35m[REVIEWER]\x1b[0m   - {}", issue);
             }
         }
+
+        if !issues.is_empty() {
+            if let Some(backend) = self.remediation_backend {
+                Self::suggest_remediations(backend, &code_payload.code, &issues);
+            }
+        }
         println!("\x1b[1;35m[REVIEWER]\x1b[0m Handing off to Debugger.");
 
         ReviewPayload {
@@ -32,6 +83,68 @@ impl ReviewerAgent {
         }
     }
 
+    /// Asks `backend` for natural-language remediation suggestions for each
+    /// finding in `issues`, printing them as they're produced.
+    ///
+    /// A single finding streams live via `call_stream`, printing each token
+    /// to stdout as it arrives — most reviews land exactly one issue, so
+    /// this is the common case and it reads like the model is thinking out
+    /// loud rather than hanging silently for a few seconds.
+    ///
+    /// Multiple findings are independent of each other, so they're instead
+    /// fanned out one request per finding via `call_batch`, which resolves
+    /// all of them concurrently on a worker pool instead of one round-trip
+    /// at a time.
+    fn suggest_remediations(backend: Backend, code: &str, issues: &[String]) {
+        if let [issue] = issues {
+            print!("\x1b[1;35m[REVIEWER]\x1b[0m AI remediation suggestion: ");
+            let mut on_token = |token: &str| {
+                print!("{}", token);
+                let _ = std::io::Write::flush(&mut std::io::stdout());
+            };
+            match backend.call_stream(&Self::remediation_system_prompt(), &Self::remediation_user_prompt(code, issue), &mut on_token) {
+                Ok(_) => println!(),
+                Err(e) => println!(
+                    "\n\x1b[1;35m[REVIEWER]\x1b[0m \x1b[2m· Could not fetch AI remediation suggestion: {}\x1b[0m",
+                    e
+                ),
+            }
+            return;
+        }
+
+        let requests = issues
+            .iter()
+            .map(|issue| {
+                (
+                    backend,
+                    Self::remediation_system_prompt(),
+                    Self::remediation_user_prompt(code, issue),
+                )
+            })
+            .collect();
+
+        for (issue, result) in issues.iter().zip(call_batch(requests)) {
+            match result {
+                Ok(suggestion) => println!(
+                    "\x1b[1;35m[REVIEWER]\x1b[0m AI remediation for \"{}\":\n{}",
+                    issue, suggestion
+                ),
+                Err(e) => println!(
+                    "\x1b[1;35m[REVIEWER]\x1b[0m \x1b[2m· Could not fetch AI remediation for \"{}\": {}\x1b[0m",
+                    issue, e
+                ),
+            }
+        }
+    }
+
+    fn remediation_system_prompt() -> String {
+        "You are a senior Rust reviewer. Given a static-analysis finding for a piece of code, suggest concise, concrete remediation.".to_string()
+    }
+
+    fn remediation_user_prompt(code: &str, issue: &str) -> String {
+        format!("Code:\n```rust\n{}\n```\n\nFinding:\n- {}", code, issue)
+    }
+
     fn review_code(&self, code: &str) -> Vec<String> {
         let mut issues = vec![];
 
@@ -198,4 +311,128 @@ impl ReviewerAgent {
         }
         max_lines
     }
+
+    /// AST-driven counterpart to `review_code`: parses the code with `syn`
+    /// and walks real items/expressions instead of scanning characters, so
+    /// magic numbers inside a macro or a raw string, or a `{...}` block that
+    /// isn't actually a function, can't produce a false positive. Falls back
+    /// to `review_code` if the code doesn't parse as valid Rust — a draft
+    /// mid-edit still gets reviewed, just with the heuristic checks.
+    fn review_code_ast(&self, code: &str) -> Vec<String> {
+        let Ok(file) = syn::parse_file(code) else {
+            return self.review_code(code);
+        };
+
+        let mut issues = vec![];
+
+        let has_main = file
+            .items
+            .iter()
+            .any(|item| matches!(item, syn::Item::Fn(f) if f.sig.ident == "main"));
+        if !has_main {
+            issues.push("Missing fn main() — code needs an entry point to be runnable".to_string());
+        }
+
+        // Comments aren't nodes in the AST at all (the parser discards
+        // them), so this check still has to look at the source text.
+        if !code.contains("//") {
+            issues.push("No comments found — add // inline comments or /// doc comments to explain the code".to_string());
+        }
+
+        let mut counter = AstCounter::default();
+        counter.visit_file(&file);
+
+        if counter.unwrap_count > 3 {
+            issues.push(format!(
+                "Found {} .unwrap() calls — more than 3 is risky; use match, if let, or ? for safer error handling",
+                counter.unwrap_count
+            ));
+        }
+
+        if counter.placeholder_count > 0 {
+            issues.push(format!(
+                "Found {} placeholder(s) (todo!() or unimplemented!()) — replace with real implementations",
+                counter.placeholder_count
+            ));
+        }
+
+        if counter.magic_number_found {
+            issues.push(
+                "Magic numbers detected — consider naming constants with 'const NAME: Type = value;' for clarity"
+                    .to_string(),
+            );
+        }
+
+        let max_fn_lines = file
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Fn(f) => Some(fn_body_line_count(f)),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+        if max_fn_lines > 40 {
+            issues.push(format!(
+                "A function spans ~{} lines — consider breaking it into smaller helper functions",
+                max_fn_lines
+            ));
+        }
+
+        issues
+    }
+}
+
+/// Lines in `f` as formatted — the whole function, signature and doc
+/// comments included, not only its body. Re-serializes just this one function with
+/// `prettyplease` rather than relying on source spans, so the count is
+/// accurate no matter how the original code was formatted (or not).
+fn fn_body_line_count(f: &syn::ItemFn) -> usize {
+    let wrapper = syn::File {
+        shebang: None,
+        attrs: vec![],
+        items: vec![syn::Item::Fn(f.clone())],
+    };
+    prettyplease::unparse(&wrapper).lines().count()
+}
+
+/// Walks a parsed file counting the same signals `review_code` scans for in
+/// raw text, but as real AST nodes: `.unwrap()` and `todo!()`/`unimplemented!()`
+/// as expressions, and bare integer literals (`Lit::Int`) as magic numbers —
+/// skipping `const` items entirely, since naming a literal via `const NAME:
+/// Type = value;` is exactly the fix this check asks for.
+#[derive(Default)]
+struct AstCounter {
+    unwrap_count: usize,
+    placeholder_count: usize,
+    magic_number_found: bool,
+}
+
+impl<'ast> Visit<'ast> for AstCounter {
+    fn visit_item_const(&mut self, _item: &'ast syn::ItemConst) {
+        // Don't recurse: a const's own initializer literal is the named
+        // constant this check is asking for, not a magic number.
+    }
+
+    fn visit_expr(&mut self, expr: &'ast syn::Expr) {
+        match expr {
+            syn::Expr::MethodCall(call) if call.method == "unwrap" && call.args.is_empty() => {
+                self.unwrap_count += 1;
+            }
+            syn::Expr::Macro(expr_macro) => {
+                if expr_macro.mac.path.is_ident("todo") || expr_macro.mac.path.is_ident("unimplemented") {
+                    self.placeholder_count += 1;
+                }
+            }
+            syn::Expr::Lit(expr_lit) => {
+                if let syn::Lit::Int(lit_int) = &expr_lit.lit {
+                    if lit_int.base10_parse::<i64>().map(|v| v.abs() > 9).unwrap_or(false) {
+                        self.magic_number_found = true;
+                    }
+                }
+            }
+            _ => {}
+        }
+        visit::visit_expr(self, expr);
+    }
 }
@@ -1,7 +1,9 @@
-use crate::messages::{FinalPayload, ValidationPayload};
+use crate::messages::{ExecOutcome, FinalPayload, RunResult, StructuralReport, ValidationPayload};
+use crate::sandbox::{run_bounded, ScratchDir, EXEC_TIMEOUT, OUTPUT_CAP_BYTES};
 use crate::thinking::{ThinkingTimer, ProcessingStage};
 use std::fs;
 use std::process::Command;
+use std::time::{Instant, SystemTime};
 
 /// The Validator agent checks whether the code addresses the user's task.
 /// Brain: Built-in — extracts meaningful keywords, checks code structure and relevance.
@@ -24,11 +26,25 @@ impl ValidatorAgent {
 
         // Stage 2: Run test cases
         ThinkingTimer::new(ProcessingStage::TestExecution, 20).start();
-        let test_passed = self.run_code_with_tests(&result.code, &test_cases);
+        let (run_result, test_case_results) = self.run_code_with_tests(&result.code, &test_cases);
+        let test_passed = run_result.return_code == 0
+            && run_result.compile_error.is_none()
+            && test_case_results.iter().all(|(_, passed)| *passed);
         if test_passed {
             println!("\x1b[1;33m[VALIDATOR]\x1b[0m \x1b[32mTest execution passed\x1b[0m");
+            for (label, passed) in &test_case_results {
+                println!("\x1b[1;33m[VALIDATOR]\x1b[0m   {} {}", if *passed { "✓" } else { "✗" }, label);
+            }
+        } else if let Some(compile_error) = &run_result.compile_error {
+            println!(
+                "\x1b[1;33m[VALIDATOR]\x1b[0m \x1b[33mCompilation failed:\x1b[0m\n{}",
+                compile_error
+            );
         } else {
             println!("\x1b[1;33m[VALIDATOR]\x1b[0m \x1b[33mTest execution failed or did not compile\x1b[0m");
+            for (label, passed) in &test_case_results {
+                println!("\x1b[1;33m[VALIDATOR]\x1b[0m   {} {}", if *passed { "✓" } else { "✗" }, label);
+            }
         }
 
         // Stage 3: Final validation (keyword checks)
@@ -39,6 +55,22 @@ impl ValidatorAgent {
         let passed = test_passed && keyword_passed;
         let final_reason = if passed {
             format!("All checks passed: {}", reason)
+        } else if run_result.outcome != ExecOutcome::Completed {
+            // A sandbox cutoff (hang, output flood) is a different kind of failure
+            // than "the code ran and gave the wrong answer" — call it out by name so
+            // the retry loop and the user can tell them apart.
+            match run_result.outcome {
+                ExecOutcome::TimedOut => format!(
+                    "Execution timed out after {:?} — the code likely hangs (infinite loop or blocking I/O)",
+                    EXEC_TIMEOUT
+                ),
+                ExecOutcome::OutputTruncated => format!(
+                    "Output exceeded the {}-byte cap and was cut off — the code likely prints unbounded output",
+                    OUTPUT_CAP_BYTES
+                ),
+                ExecOutcome::Killed => "Execution was killed before it could complete".to_string(),
+                ExecOutcome::Completed => unreachable!(),
+            }
         } else if !test_passed {
             format!("Test execution failed: {}", reason)
         } else {
@@ -51,10 +83,16 @@ impl ValidatorAgent {
             println!("\x1b[1;33m[VALIDATOR]\x1b[0m \x1b[31mValidation failed: {}\x1b[0m", final_reason);
         }
 
-        ValidationPayload { task_id: result.task_id, passed, reason: final_reason }
+        ValidationPayload {
+            task_id: result.task_id,
+            passed,
+            reason: final_reason,
+            run_result: Some(run_result),
+            test_case_results,
+        }
     }
 
-    fn generate_test_cases(&self, description: &str) -> Vec<(String, String)> {
+    pub(crate) fn generate_test_cases(&self, description: &str) -> Vec<(String, String)> {
         let desc_lower = description.to_lowercase();
         let mut test_cases = Vec::new();
 
@@ -99,61 +137,283 @@ impl ValidatorAgent {
         test_cases
     }
 
-    fn run_code_with_tests(&self, code: &str, _test_cases: &[(String, String)]) -> bool {
-        // Write code to temp file
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        let temp_file = format!("/tmp/agent_test_{}.rs", timestamp);
-        let temp_binary = format!("/tmp/agent_test_{}", timestamp);
-
-        // Write code to temp file
-        if let Err(_e) = fs::write(&temp_file, code) {
-            return false;
+    /// Compiles and runs `code`, returning a structured `RunResult` with the actual
+    /// `rustc`/binary output instead of collapsing everything to a bool. This is what
+    /// lets the retry loop hand the Coder/Debugger the real compiler diagnostics.
+    /// Both the compile and the run step are bounded by `sandbox::run_bounded`, so a
+    /// generated infinite loop or output flood can't hang the pipeline — `RunResult`
+    /// carries the resulting `ExecOutcome` (`TimedOut`/`OutputTruncated`/`Killed`)
+    /// rather than collapsing it into an ordinary failure.
+    ///
+    /// Alongside the plain run, each test case whose `expected` value is a concrete
+    /// literal (e.g. `fib(10)` → `"result 55"`) is lowered into a real `#[test]`
+    /// function asserting against the actual generated function name (matched via
+    /// `structural_report`), compiled with `rustc --test`, and the binary's
+    /// per-test `... ok` / `... FAILED` lines are parsed to decide pass/fail per case. Cases
+    /// whose expected value is free-form prose (e.g. `"non-empty output"`) fall back
+    /// to checking that the plain run produced non-empty stdout, as before.
+    fn run_code_with_tests(
+        &self,
+        code: &str,
+        test_cases: &[(String, String)],
+    ) -> (RunResult, Vec<(String, bool)>) {
+        let run_started = SystemTime::now();
+        let start = Instant::now();
+
+        let scratch = match ScratchDir::new() {
+            Ok(scratch) => scratch,
+            Err(e) => {
+                let run_result = RunResult {
+                    run_started,
+                    duration: start.elapsed(),
+                    return_code: -1,
+                    stdout: None,
+                    stderr: None,
+                    compile_error: Some(format!("Failed to create scratch dir: {}", e)),
+                    outcome: ExecOutcome::Killed,
+                };
+                let failed = test_cases.iter().map(|(input, _)| (input.clone(), false)).collect();
+                return (run_result, failed);
+            }
+        };
+        let candidate_file = scratch.join("candidate.rs");
+        let candidate_binary = scratch.join("candidate");
+
+        if let Err(e) = fs::write(&candidate_file, code) {
+            let run_result = RunResult {
+                run_started,
+                duration: start.elapsed(),
+                return_code: -1,
+                stdout: None,
+                stderr: None,
+                compile_error: Some(format!("Failed to write temp file: {}", e)),
+                outcome: ExecOutcome::Killed,
+            };
+            let failed = test_cases.iter().map(|(input, _)| (input.clone(), false)).collect();
+            return (run_result, failed);
+        }
+
+        // Compile the code, keeping rustc's stderr verbatim for diagnosis.
+        let mut compile_cmd = Command::new("rustc");
+        compile_cmd.arg(&candidate_file).arg("-o").arg(&candidate_binary);
+        let compile_output = run_bounded(compile_cmd, EXEC_TIMEOUT, OUTPUT_CAP_BYTES);
+
+        if compile_output.outcome != ExecOutcome::Completed {
+            let run_result = RunResult {
+                run_started,
+                duration: start.elapsed(),
+                return_code: -1,
+                stdout: None,
+                stderr: Some(compile_output.stderr),
+                compile_error: Some(format!("Compilation {:?}", compile_output.outcome)),
+                outcome: compile_output.outcome,
+            };
+            let failed = test_cases.iter().map(|(input, _)| (input.clone(), false)).collect();
+            return (run_result, failed);
         }
 
-        // Compile the code
-        let compile_result = Command::new("rustc")
-            .arg(&temp_file)
-            .arg("-o")
-            .arg(&temp_binary)
-            .output();
+        if compile_output.exit_code != Some(0) {
+            let run_result = RunResult {
+                run_started,
+                duration: start.elapsed(),
+                return_code: compile_output.exit_code.unwrap_or(-1),
+                stdout: None,
+                stderr: Some(compile_output.stderr.clone()),
+                compile_error: Some(compile_output.stderr),
+                outcome: ExecOutcome::Completed,
+            };
+            let failed = test_cases.iter().map(|(input, _)| (input.clone(), false)).collect();
+            return (run_result, failed);
+        }
 
-        let compile_ok = match compile_result {
-            Ok(output) => output.status.success(),
-            Err(_) => false,
+        // Run the compiled binary, bounded by the same timeout/output cap.
+        let run_cmd = Command::new(&candidate_binary);
+        let run_output = run_bounded(run_cmd, EXEC_TIMEOUT, OUTPUT_CAP_BYTES);
+
+        let run_result = RunResult {
+            run_started,
+            duration: start.elapsed(),
+            return_code: run_output.exit_code.unwrap_or(-1),
+            stdout: Some(run_output.stdout.clone()),
+            stderr: if run_output.outcome == ExecOutcome::Completed && run_output.exit_code == Some(0) {
+                None
+            } else {
+                Some(run_output.stderr.clone())
+            },
+            compile_error: None,
+            outcome: run_output.outcome,
         };
 
-        if !compile_ok {
-            // Cleanup
-            let _ = fs::remove_file(&temp_file);
-            return false;
+        if run_result.outcome != ExecOutcome::Completed {
+            let failed = test_cases.iter().map(|(input, _)| (input.clone(), false)).collect();
+            return (run_result, failed);
         }
 
-        // Run the binary
-        let run_result = Command::new(&temp_binary)
-            .output();
+        // Separate cases we can turn into a real assertion from free-form ones.
+        let report = Self::structural_report(code);
+        let mut asserted: Vec<(&(String, String), String)> = Vec::new();
+        let mut test_case_results = Vec::new();
+
+        for case in test_cases {
+            match Self::build_assertion(case, &report.functions) {
+                Some(assertion) => asserted.push((case, assertion)),
+                None => {
+                    let passed = run_result
+                        .stdout
+                        .as_deref()
+                        .is_some_and(|s| !s.trim().is_empty());
+                    test_case_results.push((case.0.clone(), passed));
+                }
+            }
+        }
 
-        let run_ok = match run_result {
-            Ok(output) => {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                // Check that it produced some output
-                !stdout.trim().is_empty()
+        if !asserted.is_empty() {
+            let harness_results = self.run_test_harness(code, &asserted);
+            for (i, (case, _)) in asserted.iter().enumerate() {
+                let passed = harness_results.get(i).copied().unwrap_or(false);
+                test_case_results.push((case.0.clone(), passed));
             }
-            Err(_) => false,
+        }
+
+        (run_result, test_case_results)
+    }
+
+    /// Appends a `#[cfg(test)] mod generated_validator_tests { ... }` block containing
+    /// one `#[test]` per asserted case, compiles it with `rustc --test` and runs the
+    /// resulting harness binary — both bounded by `sandbox::run_bounded` — then parses
+    /// its libtest output for per-case pass/fail (in the same order as `asserted`).
+    /// Returns all `false` if the harness itself fails to compile, run, or times out —
+    /// a harness compile failure almost always means the assertion's function
+    /// name/arity guess didn't match the real generated code.
+    fn run_test_harness(&self, code: &str, asserted: &[(&(String, String), String)]) -> Vec<bool> {
+        let all_failed = vec![false; asserted.len()];
+
+        let scratch = match ScratchDir::new() {
+            Ok(scratch) => scratch,
+            Err(_) => return all_failed,
         };
+        let harness_file = scratch.join("harness.rs");
+        let harness_binary = scratch.join("harness");
+
+        let mut harness_code = code.to_string();
+        harness_code.push_str("\n\n#[cfg(test)]\nmod generated_validator_tests {\n    use super::*;\n\n");
+        for (i, (_, assertion)) in asserted.iter().enumerate() {
+            harness_code.push_str(&format!(
+                "    #[test]\n    fn case_{}() {{\n        {}\n    }}\n\n",
+                i + 1,
+                assertion
+            ));
+        }
+        harness_code.push_str("}\n");
 
-        // Cleanup
-        let _ = fs::remove_file(&temp_file);
-        let _ = fs::remove_file(&temp_binary);
+        if fs::write(&harness_file, &harness_code).is_err() {
+            return all_failed;
+        }
+
+        let mut compile_cmd = Command::new("rustc");
+        compile_cmd.arg("--test").arg(&harness_file).arg("-o").arg(&harness_binary);
+        let compile_output = run_bounded(compile_cmd, EXEC_TIMEOUT, OUTPUT_CAP_BYTES);
+        if compile_output.outcome != ExecOutcome::Completed || compile_output.exit_code != Some(0) {
+            return all_failed;
+        }
 
-        run_ok
+        let run_cmd = Command::new(&harness_binary);
+        let run_output = run_bounded(run_cmd, EXEC_TIMEOUT, OUTPUT_CAP_BYTES);
+        if run_output.outcome != ExecOutcome::Completed {
+            return all_failed;
+        }
+
+        // libtest prints one "test <path> ... ok|FAILED" line per #[test] function.
+        let mut results = vec![false; asserted.len()];
+        for i in 0..asserted.len() {
+            let marker = format!("generated_validator_tests::case_{} ... ", i + 1);
+            if let Some(line) = run_output.stdout.lines().find(|l| l.contains(&marker)) {
+                results[i] = line.trim_end().ends_with("ok");
+            }
+        }
+        results
+    }
+
+    /// Tries to lower a `(input, expected)` test case into a real `assert_eq!`
+    /// statement against one of the real generated functions. Returns `None` for
+    /// free-form cases (e.g. `"non-empty output"`) that have no concrete literal to
+    /// assert against — those fall back to the old non-empty-stdout check.
+    fn build_assertion(case: &(String, String), functions: &[String]) -> Option<String> {
+        let (input, expected) = case;
+
+        // Expect `input` to look like a call: `name(args)`.
+        let open = input.find('(')?;
+        let close = input.rfind(')')?;
+        if close < open {
+            return None;
+        }
+        let name_hint = input[..open].trim();
+        let args = &input[open + 1..close];
+
+        // Expect `expected` to resolve to a concrete Rust literal.
+        let literal = if let Some(rest) = expected.strip_prefix("result ") {
+            rest.trim().to_string()
+        } else if expected == "true" || expected == "false" {
+            expected.clone()
+        } else {
+            return None;
+        };
+
+        // Match the hinted name against the real parsed function names — exact match
+        // first, then substring match in either direction (handles e.g. "fib" vs the
+        // generated "fibonacci").
+        let real_name = functions
+            .iter()
+            .find(|f| f.as_str() == name_hint)
+            .or_else(|| {
+                functions
+                    .iter()
+                    .find(|f| f.contains(name_hint) || name_hint.contains(f.as_str()))
+            })?;
+
+        Some(format!("assert_eq!({}({}), {});", real_name, args, literal))
+    }
+
+    /// Parses `code` into a real AST via `syn` and extracts structural facts instead of
+    /// lowercasing-and-`contains()`ing the source. Falls back to a best-effort report
+    /// with `parse_ok: false` when the snippet doesn't parse (e.g. a partial/broken draft).
+    fn structural_report(code: &str) -> StructuralReport {
+        let file = match syn::parse_file(code) {
+            Ok(file) => file,
+            Err(_) => {
+                return StructuralReport {
+                    functions: Vec::new(),
+                    has_main: code.contains("fn main()"),
+                    parse_ok: false,
+                };
+            }
+        };
+
+        let mut functions = Vec::new();
+        let mut has_main = false;
+
+        for item in &file.items {
+            if let syn::Item::Fn(item_fn) = item {
+                let name = item_fn.sig.ident.to_string();
+                if name == "main" {
+                    has_main = true;
+                }
+                functions.push(name);
+            }
+        }
+
+        StructuralReport {
+            functions,
+            has_main,
+            parse_ok: true,
+        }
     }
 
     fn validate(&self, code: &str, task_description: &str) -> (bool, String) {
-        // --- Structural check 1: fn main() must exist ---
-        if !code.contains("fn main()") {
+        let report = Self::structural_report(code);
+
+        // --- Structural check 1: fn main() must exist as a real parsed item ---
+        if !report.has_main {
             return (
                 false,
                 "Code is missing fn main() — no runnable entry point".to_string(),
@@ -230,10 +490,20 @@ impl ValidatorAgent {
         let mut unmatched_keywords: Vec<String> = Vec::new();
 
         for kw in &keywords {
-            let direct_match = code_lower.contains(kw.as_str());
+            // Prefer matching against real identifiers (function names) parsed from
+            // the AST; fall back to the raw-text check only when parsing failed.
+            let identifier_match = report
+                .functions
+                .iter()
+                .any(|f| f.to_lowercase().contains(kw.as_str()));
+            let direct_match = identifier_match || (!report.parse_ok && code_lower.contains(kw.as_str()));
             let synonym_match = synonyms.iter().any(|(key, syns)| {
                 (kw.starts_with(key) || key.starts_with(kw.as_str()))
-                    && syns.iter().any(|s| code_lower.contains(*s) || code.contains(*s))
+                    && syns.iter().any(|s| {
+                        report.functions.iter().any(|f| f.eq_ignore_ascii_case(s))
+                            || code_lower.contains(*s)
+                            || code.contains(*s)
+                    })
             });
 
             if direct_match || synonym_match {
@@ -245,8 +515,19 @@ impl ValidatorAgent {
 
         let match_ratio = matched_keywords.len() as f64 / keywords.len() as f64;
 
-        // --- Structural check 3: Code should contain at least one fn definition ---
-        let fn_count = code.matches("fn ").count();
+        // --- Structural check 3: Code should define at least one real function besides main ---
+        let fn_count = if report.parse_ok {
+            // Methods inside `impl` blocks count too, not just top-level fns.
+            syn::parse_file(code).map_or(0, |file| {
+                file.items.iter().map(|item| match item {
+                    syn::Item::Fn(_) => 1,
+                    syn::Item::Impl(imp) => imp.items.iter().filter(|i| matches!(i, syn::ImplItem::Fn(_))).count(),
+                    _ => 0,
+                }).sum::<usize>()
+            })
+        } else {
+            code.matches("fn ").count()
+        };
         if fn_count < 2 {
             // At least a helper fn + main
             return (
@@ -1,4 +1,5 @@
-//! AI client — HTTP functions for calling OpenAI, DeepSeek, and Anthropic APIs.
+//! AI client — HTTP functions for calling OpenAI, DeepSeek, and Anthropic APIs,
+//! plus a local/offline backend for running fully without cloud API keys.
 //!
 //! HOW TO USE:
 //! Set these environment variables in your terminal before running:
@@ -6,98 +7,305 @@
 //!   export DEEPSEEK_API_KEY="your-key"
 //!   export ANTHROPIC_API_KEY="your-key"
 //!
+//! For the local backend (Ollama, llama.cpp, or anything else speaking the
+//! OpenAI chat-completions schema), no key is needed — set `LOCAL_MODEL` to
+//! the model name and optionally `LOCAL_ENDPOINT` to point at a non-default
+//! server:
+//!   export LOCAL_MODEL="llama3"
+//!   export LOCAL_ENDPOINT="http://localhost:11434/v1/chat/completions"  # default
+//!
 //! Each function takes a system prompt (the agent's role/instructions) and a
 //! user prompt (the actual task content), and returns the AI's response text.
 
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
 /// Result type used across all AI calls.
 pub type AiResult = Result<String, String>;
 
-// ─── OpenAI (GPT-4o) ─────────────────────────────────────────────────────────
+/// Which inference backend an agent should use. Lets each agent be pointed
+/// at a local model instead of a cloud provider — e.g. for a password
+/// manager, where sending code or secrets to an external API is itself a
+/// concern — without changing anything but this one value. Also selects
+/// which `ChatBackend` impl `Backend::from_config` builds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Backend {
+    OpenAi,
+    DeepSeek,
+    Anthropic,
+    /// An OpenAI-compatible local server (Ollama, llama.cpp, ...) — see
+    /// `LocalBackend`. Fully offline: no API key, no network egress beyond
+    /// `localhost` (or wherever `LOCAL_ENDPOINT` points).
+    Local,
+}
 
-/// Calls GPT-4o (OpenAI). Used by: CoderAgent.
-/// Role: Coding specialist — writes high-quality Rust code.
-pub fn call_gpt(system: &str, user: &str) -> AiResult {
-    let key = std::env::var("OPENAI_API_KEY").map_err(|_| {
-        "Missing OPENAI_API_KEY. Get one at platform.openai.com, then run:\n  export OPENAI_API_KEY=\"your-key\"".to_string()
-    })?;
+impl Backend {
+    /// This provider's default model name (used when no `BackendConfig`
+    /// override is given) — `Backend::Local` has no hardcoded default since
+    /// the whole point of a local server is running whatever's loaded, so it
+    /// reads `LOCAL_MODEL` instead.
+    fn default_model(&self) -> Result<String, String> {
+        match self {
+            Backend::OpenAi => Ok("gpt-4o".to_string()),
+            Backend::DeepSeek => Ok("deepseek-coder".to_string()),
+            Backend::Anthropic => Ok("claude-3-5-sonnet-20241022".to_string()),
+            Backend::Local => std::env::var("LOCAL_MODEL").map_err(|_| {
+                "Missing LOCAL_MODEL. Set it to the model name your local server has loaded, e.g.:\n  export LOCAL_MODEL=\"llama3\"".to_string()
+            }),
+        }
+    }
 
-    let body = json!({
-        "model": "gpt-4o",
-        "messages": [
-            { "role": "system", "content": system },
-            { "role": "user",   "content": user   }
-        ],
-        "max_tokens": 2000,
-        "temperature": 0.2
-    });
+    /// Builds this provider's `ChatBackend` with its default model/config.
+    fn default_chat_backend(&self) -> Result<Box<dyn ChatBackend>, String> {
+        let config = BackendConfig {
+            provider: *self,
+            model: self.default_model()?,
+            base_url: None,
+            temperature: None,
+            max_tokens: None,
+        };
+        Backend::from_config(&config)
+    }
 
-    let client = reqwest::blocking::Client::new();
-    let resp = client
-        .post("https://api.openai.com/v1/chat/completions")
-        .bearer_auth(key)
-        .json(&body)
-        .send()
-        .map_err(|e| format!("OpenAI request failed: {e}"))?;
+    /// Calls this backend with `system`/`user`, via its default `ChatBackend`.
+    pub fn call(&self, system: &str, user: &str) -> AiResult {
+        self.default_chat_backend()?.complete(system, user)
+    }
 
-    let json: Value = resp.json().map_err(|e| format!("OpenAI parse failed: {e}"))?;
-    extract_openai_text(&json)
+    /// Like `call`, but lets the model call back into `tools` before giving
+    /// its final answer — see `ChatBackend::complete_with_tools`.
+    /// `Backend::Local` has no tool-calling variant, so it falls back to a
+    /// plain `complete`.
+    pub fn call_with_tools(&self, system: &str, user: &str, tools: &[ToolSpec]) -> AiResult {
+        self.default_chat_backend()?.complete_with_tools(system, user, tools)
+    }
+
+    /// Like `call`, but invokes `on_token` with each chunk of the reply as it
+    /// arrives instead of waiting for the whole completion — see
+    /// `ChatBackend::complete_stream`.
+    pub fn call_stream(
+        &self,
+        system: &str,
+        user: &str,
+        on_token: &mut dyn FnMut(&str),
+    ) -> AiResult {
+        self.default_chat_backend()?.complete_stream(system, user, on_token)
+    }
+}
+
+/// Runs a batch of independent `(backend, system, user)` calls concurrently
+/// on a worker pool sized to the CPU count, and returns their results in the
+/// same order as `requests` — regardless of which worker finishes first.
+/// Each `call` is a blocking HTTP request routed through `ChatBackend`, so a
+/// serial loop over N requests costs N round-trips; spreading them across
+/// workers lets independent sub-prompts (e.g. `ReviewerAgent` asking for a
+/// remediation suggestion per finding) resolve in parallel instead.
+pub fn call_batch(requests: Vec<(Backend, String, String)>) -> Vec<AiResult> {
+    let total = requests.len();
+    if total == 0 {
+        return Vec::new();
+    }
+
+    let worker_count = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(total);
+
+    let (job_tx, job_rx) = std::sync::mpsc::channel::<(usize, Backend, String, String)>();
+    let job_rx = std::sync::Arc::new(std::sync::Mutex::new(job_rx));
+    let (result_tx, result_rx) = std::sync::mpsc::channel::<(usize, AiResult)>();
+
+    for (index, (backend, system, user)) in requests.into_iter().enumerate() {
+        job_tx
+            .send((index, backend, system, user))
+            .expect("receiver outlives every send: workers are joined below");
+    }
+    drop(job_tx);
+
+    let workers: Vec<_> = (0..worker_count)
+        .map(|_| {
+            let job_rx = std::sync::Arc::clone(&job_rx);
+            let result_tx = result_tx.clone();
+            std::thread::spawn(move || loop {
+                let job = job_rx.lock().unwrap().recv();
+                let Ok((index, backend, system, user)) = job else {
+                    break;
+                };
+                let result = backend.call(&system, &user);
+                if result_tx.send((index, result)).is_err() {
+                    break;
+                }
+            })
+        })
+        .collect();
+    drop(result_tx);
+
+    let mut results: Vec<Option<AiResult>> = (0..total).map(|_| None).collect();
+    for (index, result) in result_rx {
+        results[index] = Some(result);
+    }
+    for worker in workers {
+        let _ = worker.join();
+    }
+
+    results
+        .into_iter()
+        .map(|r| r.expect("every dispatched job sends back exactly one result"))
+        .collect()
+}
+
+// ─── Tool-calling ────────────────────────────────────────────────────────────
+
+/// A Rust-side implementation of a tool the model can call. Receives the
+/// arguments object the model supplied (already parsed from whatever shape
+/// the provider sent) and returns the tool's result as text, or an error
+/// that gets fed back to the model so it can try something else.
+pub type ToolFn = Box<dyn Fn(&Value) -> Result<String, String>>;
+
+/// A tool exposed to the model's tool-calling API, together with the Rust
+/// closure that actually runs it. `name`/`description`/`json_schema` are
+/// sent to the provider verbatim (as an OpenAI `function` or an Anthropic
+/// `tool` definition, depending which `call_*_with_tools` is used);
+/// `handler` is invoked locally once the model asks for this tool by name.
+///
+/// Tools whose `name` starts with `may_` (e.g. `"may_delete_file"`) are
+/// treated as destructive: `call_with_tools` asks for interactive
+/// confirmation via `confirm_destructive_tool` before running them, and
+/// skips the call (reporting the decline back to the model) if declined.
+pub struct ToolSpec {
+    pub name: String,
+    pub description: String,
+    pub json_schema: Value,
+    pub handler: ToolFn,
+}
+
+impl ToolSpec {
+    pub fn new(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        json_schema: Value,
+        handler: ToolFn,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            json_schema,
+            handler,
+        }
+    }
+
+    fn requires_confirmation(&self) -> bool {
+        self.name.starts_with("may_")
+    }
+}
+
+/// Hard cap on how many request/dispatch round-trips `call_with_tools` will
+/// run before giving up and returning an error — guards against a model
+/// that keeps calling tools instead of ever returning plain text.
+const MAX_TOOL_STEPS: usize = 8;
+
+/// Asks the user to approve running a `may_`-prefixed (destructive) tool.
+/// Anything other than `y`/`yes` (including a read failure) counts as a
+/// decline.
+fn confirm_destructive_tool(tool_name: &str) -> bool {
+    use std::io::Write;
+    print!("[ai_client] '{tool_name}' is a destructive tool. Run it? [y/N] ");
+    let _ = std::io::stdout().flush();
+
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Looks up `name` in `tools` and runs it, gating `may_`-prefixed tools
+/// behind `confirm_destructive_tool`.
+fn dispatch_tool(tools: &[ToolSpec], name: &str, args: &Value) -> Result<String, String> {
+    let tool = tools
+        .iter()
+        .find(|t| t.name == name)
+        .ok_or_else(|| format!("No such tool registered: {name}"))?;
+
+    if tool.requires_confirmation() && !confirm_destructive_tool(&tool.name) {
+        return Err(format!("User declined to run destructive tool: {name}"));
+    }
+
+    (tool.handler)(args)
 }
 
-// ─── DeepSeek (deepseek-coder) ───────────────────────────────────────────────
+// ─── Local (Ollama / llama.cpp, OpenAI-compatible) ──────────────────────────
 
-/// Calls DeepSeek-Coder. Used by: DebuggerAgent.
-/// Role: Debugging and optimization specialist.
-pub fn call_deepseek(system: &str, user: &str) -> AiResult {
-    let key = std::env::var("DEEPSEEK_API_KEY").map_err(|_| {
-        "Missing DEEPSEEK_API_KEY. Get one at platform.deepseek.com, then run:\n  export DEEPSEEK_API_KEY=\"your-key\"".to_string()
-    })?;
+/// Default endpoint for a local Ollama server's OpenAI-compatible API.
+const DEFAULT_LOCAL_ENDPOINT: &str = "http://localhost:11434/v1/chat/completions";
 
-    // DeepSeek uses the same request format as OpenAI
+// ─── Anthropic (Claude) ──────────────────────────────────────────────────────
+
+/// Shared single-shot request for Anthropic's Messages API — `AnthropicBackend::complete`
+/// is a thin wrapper around this with its own URL, model, and
+/// `max_tokens` defaults.
+fn call_claude_shaped(
+    url: &str,
+    key: &str,
+    model: &str,
+    max_tokens: u32,
+    system: &str,
+    user: &str,
+) -> AiResult {
     let body = json!({
-        "model": "deepseek-coder",
+        "model": model,
+        "max_tokens": max_tokens,
+        "system": system,
         "messages": [
-            { "role": "system", "content": system },
-            { "role": "user",   "content": user   }
-        ],
-        "max_tokens": 2000,
-        "temperature": 0.1
+            { "role": "user", "content": user }
+        ]
     });
 
     let client = reqwest::blocking::Client::new();
     let resp = client
-        .post("https://api.deepseek.com/chat/completions")
-        .bearer_auth(key)
+        .post(url)
+        .header("x-api-key", key)
+        .header("anthropic-version", "2023-06-01")
+        .header("content-type", "application/json")
         .json(&body)
         .send()
-        .map_err(|e| format!("DeepSeek request failed: {e}"))?;
+        .map_err(|e| format!("Anthropic request failed: {e}"))?;
 
-    let json: Value = resp.json().map_err(|e| format!("DeepSeek parse failed: {e}"))?;
-    extract_openai_text(&json)
+    let json: Value = resp.json().map_err(|e| format!("Anthropic parse failed: {e}"))?;
+    extract_claude_text(&json)
 }
 
-// ─── Anthropic (Claude) ──────────────────────────────────────────────────────
-
-/// Calls Claude (Anthropic). Used by: PlannerAgent, ReviewerAgent, ValidatorAgent, CoordinatorAgent.
-/// Roles: Architecture, Security & Docs, Testing.
-pub fn call_claude(system: &str, user: &str) -> AiResult {
-    let key = std::env::var("ANTHROPIC_API_KEY").map_err(|_| {
-        "Missing ANTHROPIC_API_KEY. Get one at console.anthropic.com, then run:\n  export ANTHROPIC_API_KEY=\"your-key\"".to_string()
-    })?;
-
+/// Shared streaming request for Anthropic's Messages API —
+/// `AnthropicBackend::complete_stream` is a thin wrapper around this with
+/// its own URL, model, and `max_tokens` defaults. Sets `"stream": true` and
+/// reads the response body as server-sent events: each `data:` line is a
+/// JSON event, and a `content_block_delta` event's `delta.text` is one chunk
+/// of the reply. `on_token` is invoked once per chunk in arrival order; the
+/// full reply is accumulated and returned at the end, same as the
+/// non-streaming call.
+fn call_claude_shaped_stream(
+    url: &str,
+    key: &str,
+    model: &str,
+    max_tokens: u32,
+    system: &str,
+    user: &str,
+    on_token: &mut dyn FnMut(&str),
+) -> AiResult {
     let body = json!({
-        "model": "claude-3-5-sonnet-20241022",
-        "max_tokens": 2000,
+        "model": model,
+        "max_tokens": max_tokens,
         "system": system,
         "messages": [
             { "role": "user", "content": user }
-        ]
+        ],
+        "stream": true,
     });
 
     let client = reqwest::blocking::Client::new();
     let resp = client
-        .post("https://api.anthropic.com/v1/messages")
+        .post(url)
         .header("x-api-key", key)
         .header("anthropic-version", "2023-06-01")
         .header("content-type", "application/json")
@@ -105,12 +313,312 @@ pub fn call_claude(system: &str, user: &str) -> AiResult {
         .send()
         .map_err(|e| format!("Anthropic request failed: {e}"))?;
 
-    let json: Value = resp.json().map_err(|e| format!("Anthropic parse failed: {e}"))?;
-    extract_claude_text(&json)
+    let mut full_text = String::new();
+    for line in std::io::BufRead::lines(std::io::BufReader::new(resp)) {
+        let line = line.map_err(|e| format!("Anthropic stream read failed: {e}"))?;
+        let Some(data) = line.strip_prefix("data: ") else {
+            continue;
+        };
+        let Ok(event) = serde_json::from_str::<Value>(data) else {
+            continue;
+        };
+        if event["type"] == "content_block_delta" {
+            if let Some(text) = event["delta"]["text"].as_str() {
+                on_token(text);
+                full_text.push_str(text);
+            }
+        }
+    }
+    Ok(full_text)
+}
+
+/// Shared tool-calling loop for Anthropic's Messages API —
+/// `AnthropicBackend::complete_with_tools` is a thin wrapper around this
+/// with its own URL, model, and `max_tokens` defaults. Each round, Claude
+/// may return one or more `tool_use` blocks in `content` instead of (or
+/// alongside) text; this runs the requested tools and resends their
+/// `tool_result`s until Claude answers with text only, or `MAX_TOOL_STEPS`
+/// round-trips have passed.
+fn call_claude_shaped_with_tools(
+    url: &str,
+    key: &str,
+    model: &str,
+    max_tokens: u32,
+    system: &str,
+    user: &str,
+    tools: &[ToolSpec],
+) -> AiResult {
+    let client = reqwest::blocking::Client::new();
+    let mut messages = vec![json!({ "role": "user", "content": user })];
+
+    for _ in 0..MAX_TOOL_STEPS {
+        let body = json!({
+            "model": model,
+            "max_tokens": max_tokens,
+            "system": system,
+            "messages": messages,
+            "tools": claude_tool_defs(tools),
+        });
+
+        let resp = client
+            .post(url)
+            .header("x-api-key", key)
+            .header("anthropic-version", "2023-06-01")
+            .header("content-type", "application/json")
+            .json(&body)
+            .send()
+            .map_err(|e| format!("Anthropic request failed: {e}"))?;
+
+        let response: Value = resp.json().map_err(|e| format!("Anthropic parse failed: {e}"))?;
+        let content = response["content"]
+            .as_array()
+            .cloned()
+            .ok_or_else(|| format!("Unexpected Anthropic response format: {response}"))?;
+
+        let tool_uses: Vec<&Value> = content
+            .iter()
+            .filter(|block| block["type"] == "tool_use")
+            .collect();
+
+        if tool_uses.is_empty() {
+            return Ok(content
+                .iter()
+                .filter_map(|block| block["text"].as_str())
+                .collect::<Vec<_>>()
+                .join(""));
+        }
+
+        let tool_results: Vec<Value> = tool_uses
+            .iter()
+            .map(|block| {
+                let tool_use_id = block["id"].as_str().unwrap_or_default();
+                let name = block["name"].as_str().unwrap_or_default();
+                let result = dispatch_tool(tools, name, &block["input"])
+                    .unwrap_or_else(|e| format!("Error: {e}"));
+                json!({
+                    "type": "tool_result",
+                    "tool_use_id": tool_use_id,
+                    "content": result,
+                })
+            })
+            .collect();
+
+        messages.push(json!({ "role": "assistant", "content": content }));
+        messages.push(json!({ "role": "user", "content": tool_results }));
+    }
+
+    Err(format!(
+        "Exceeded {MAX_TOOL_STEPS} tool-calling steps without a final answer"
+    ))
+}
+
+/// Builds the Anthropic `tools` array from `ToolSpec`s — each tool's
+/// `json_schema` becomes its `input_schema`.
+fn claude_tool_defs(tools: &[ToolSpec]) -> Vec<Value> {
+    tools
+        .iter()
+        .map(|t| {
+            json!({
+                "name": t.name,
+                "description": t.description,
+                "input_schema": t.json_schema,
+            })
+        })
+        .collect()
+}
+
+/// Builds the OpenAI/DeepSeek `tools` array from `ToolSpec`s — each tool's
+/// `json_schema` becomes its function's `parameters`.
+fn openai_tool_defs(tools: &[ToolSpec]) -> Vec<Value> {
+    tools
+        .iter()
+        .map(|t| {
+            json!({
+                "type": "function",
+                "function": {
+                    "name": t.name,
+                    "description": t.description,
+                    "parameters": t.json_schema,
+                }
+            })
+        })
+        .collect()
+}
+
+/// Shared tool-calling loop for OpenAI-shaped APIs (OpenAI and DeepSeek use
+/// the same `chat/completions` request/response shape): resends `messages`
+/// with `tools` attached, and for each `choices[0].message.tool_calls`
+/// entry the model returns, dispatches the named tool and appends its
+/// result as a `role: "tool"` message before resending — repeating until
+/// the model replies with plain text, or `MAX_TOOL_STEPS` round-trips have
+/// passed.
+fn call_openai_shaped_with_tools(
+    url: &str,
+    key: Option<&str>,
+    model: &str,
+    temperature: f32,
+    max_tokens: u32,
+    system: &str,
+    user: &str,
+    tools: &[ToolSpec],
+) -> AiResult {
+    let client = reqwest::blocking::Client::new();
+    let mut messages = vec![
+        json!({ "role": "system", "content": system }),
+        json!({ "role": "user", "content": user }),
+    ];
+
+    for _ in 0..MAX_TOOL_STEPS {
+        let body = json!({
+            "model": model,
+            "messages": messages,
+            "tools": openai_tool_defs(tools),
+            "max_tokens": max_tokens,
+            "temperature": temperature
+        });
+
+        let mut request = client.post(url).json(&body);
+        if let Some(key) = key {
+            request = request.bearer_auth(key);
+        }
+        let resp = request
+            .send()
+            .map_err(|e| format!("Request to {url} failed: {e}"))?;
+
+        let response: Value = resp.json().map_err(|e| format!("Response parse failed: {e}"))?;
+        let message = &response["choices"][0]["message"];
+        let tool_calls = message["tool_calls"].as_array().cloned().unwrap_or_default();
+
+        if tool_calls.is_empty() {
+            return message["content"]
+                .as_str()
+                .map(|s| s.to_string())
+                .ok_or_else(|| format!("Unexpected API response format: {response}"));
+        }
+
+        messages.push(message.clone());
+
+        for call in &tool_calls {
+            let call_id = call["id"].as_str().unwrap_or_default();
+            let name = call["function"]["name"].as_str().unwrap_or_default();
+            let args: Value = call["function"]["arguments"]
+                .as_str()
+                .and_then(|s| serde_json::from_str(s).ok())
+                .unwrap_or(Value::Null);
+
+            let result = dispatch_tool(tools, name, &args).unwrap_or_else(|e| format!("Error: {e}"));
+
+            messages.push(json!({
+                "role": "tool",
+                "tool_call_id": call_id,
+                "content": result,
+            }));
+        }
+    }
+
+    Err(format!(
+        "Exceeded {MAX_TOOL_STEPS} tool-calling steps without a final answer"
+    ))
 }
 
 // ─── Helpers ─────────────────────────────────────────────────────────────────
 
+/// Shared single-shot request for any OpenAI-shaped `chat/completions`
+/// endpoint (OpenAI, DeepSeek, and OpenAI-compatible local servers all use
+/// this request/response shape) — `OpenAiBackend`, `DeepSeekBackend`, and
+/// `LocalBackend` all call this with their own URL, model, and sampling
+/// defaults. `key` is omitted from the request entirely when `None`, for
+/// backends (like a local server) that need no auth.
+fn call_openai_shaped(
+    url: &str,
+    key: Option<&str>,
+    model: &str,
+    temperature: f32,
+    max_tokens: u32,
+    system: &str,
+    user: &str,
+) -> AiResult {
+    let body = json!({
+        "model": model,
+        "messages": [
+            { "role": "system", "content": system },
+            { "role": "user",   "content": user   }
+        ],
+        "max_tokens": max_tokens,
+        "temperature": temperature
+    });
+
+    let client = reqwest::blocking::Client::new();
+    let mut request = client.post(url).json(&body);
+    if let Some(key) = key {
+        request = request.bearer_auth(key);
+    }
+    let resp = request
+        .send()
+        .map_err(|e| format!("Request to {url} failed: {e}"))?;
+
+    let json: Value = resp.json().map_err(|e| format!("Response parse failed: {e}"))?;
+    extract_openai_text(&json)
+}
+
+/// Shared streaming request for any OpenAI-shaped `chat/completions`
+/// endpoint — `OpenAiBackend`, `DeepSeekBackend`, and `LocalBackend` all call
+/// this for `complete_stream`. Sets `"stream": true` and reads the response
+/// body as server-sent events: each `data:` line is a JSON chunk, and
+/// `choices[0].delta.content` is the next fragment of the reply (a `data:
+/// [DONE]` line ends the stream). `on_token` is invoked once per fragment in
+/// arrival order; the full reply is accumulated and returned at the end,
+/// same as the non-streaming call.
+fn call_openai_shaped_stream(
+    url: &str,
+    key: Option<&str>,
+    model: &str,
+    temperature: f32,
+    max_tokens: u32,
+    system: &str,
+    user: &str,
+    on_token: &mut dyn FnMut(&str),
+) -> AiResult {
+    let body = json!({
+        "model": model,
+        "messages": [
+            { "role": "system", "content": system },
+            { "role": "user",   "content": user   }
+        ],
+        "max_tokens": max_tokens,
+        "temperature": temperature,
+        "stream": true,
+    });
+
+    let client = reqwest::blocking::Client::new();
+    let mut request = client.post(url).json(&body);
+    if let Some(key) = key {
+        request = request.bearer_auth(key);
+    }
+    let resp = request
+        .send()
+        .map_err(|e| format!("Request to {url} failed: {e}"))?;
+
+    let mut full_text = String::new();
+    for line in std::io::BufRead::lines(std::io::BufReader::new(resp)) {
+        let line = line.map_err(|e| format!("Stream read failed: {e}"))?;
+        let Some(data) = line.strip_prefix("data: ") else {
+            continue;
+        };
+        if data == "[DONE]" {
+            break;
+        }
+        let Ok(chunk) = serde_json::from_str::<Value>(data) else {
+            continue;
+        };
+        if let Some(delta) = chunk["choices"][0]["delta"]["content"].as_str() {
+            on_token(delta);
+            full_text.push_str(delta);
+        }
+    }
+    Ok(full_text)
+}
+
 /// Extracts the assistant's reply text from OpenAI / DeepSeek JSON response.
 fn extract_openai_text(json: &Value) -> AiResult {
     json["choices"][0]["message"]["content"]
@@ -126,3 +634,352 @@ fn extract_claude_text(json: &Value) -> AiResult {
         .map(|s| s.to_string())
         .ok_or_else(|| format!("Unexpected Anthropic response format: {json}"))
 }
+
+// ─── ChatBackend: provider-agnostic, config-driven backends ─────────────────
+
+/// Provider-agnostic chat backend. Each implementor owns its own model
+/// name, base URL, and sampling parameters, so an agent can hold a
+/// `Box<dyn ChatBackend>` instead of calling a fixed provider function —
+/// swapping providers (or substituting a mock in tests) means handing the
+/// agent a different `Box<dyn ChatBackend>`, not editing its code.
+pub trait ChatBackend {
+    fn complete(&self, system: &str, user: &str) -> AiResult;
+
+    /// Whether `complete_with_tools` actually runs a tool-calling loop for
+    /// this backend.
+    fn supports_tools(&self) -> bool {
+        false
+    }
+
+    /// Tool-calling variant of `complete`. The default implementation
+    /// ignores `tools` and just calls `complete` — override alongside
+    /// `supports_tools() -> true` for a backend that can dispatch tool
+    /// calls (see `OpenAiBackend`, `DeepSeekBackend`, `AnthropicBackend`).
+    fn complete_with_tools(&self, system: &str, user: &str, _tools: &[ToolSpec]) -> AiResult {
+        self.complete(system, user)
+    }
+
+    /// Streaming variant of `complete`: invokes `on_token` with each chunk
+    /// of the reply as it arrives instead of waiting for the whole
+    /// completion. The default implementation has no real streaming
+    /// transport to fall back on, so it just calls `complete` and reports
+    /// the full text as a single chunk — override for a backend whose API
+    /// supports server-sent events (see `OpenAiBackend`, `DeepSeekBackend`,
+    /// `AnthropicBackend`, `LocalBackend`).
+    fn complete_stream(
+        &self,
+        system: &str,
+        user: &str,
+        on_token: &mut dyn FnMut(&str),
+    ) -> AiResult {
+        let text = self.complete(system, user)?;
+        on_token(&text);
+        Ok(text)
+    }
+}
+
+/// `ChatBackend` for OpenAI's `chat/completions` API.
+pub struct OpenAiBackend {
+    pub model: String,
+    pub base_url: String,
+    pub api_key: String,
+    pub temperature: f32,
+    pub max_tokens: u32,
+}
+
+impl OpenAiBackend {
+    pub fn new(model: impl Into<String>) -> Result<Self, String> {
+        let api_key = std::env::var("OPENAI_API_KEY").map_err(|_| {
+            "Missing OPENAI_API_KEY. Get one at platform.openai.com, then run:\n  export OPENAI_API_KEY=\"your-key\"".to_string()
+        })?;
+        Ok(Self {
+            model: model.into(),
+            base_url: "https://api.openai.com/v1/chat/completions".to_string(),
+            api_key,
+            temperature: 0.2,
+            max_tokens: 2000,
+        })
+    }
+}
+
+impl ChatBackend for OpenAiBackend {
+    fn complete(&self, system: &str, user: &str) -> AiResult {
+        call_openai_shaped(
+            &self.base_url,
+            Some(&self.api_key),
+            &self.model,
+            self.temperature,
+            self.max_tokens,
+            system,
+            user,
+        )
+    }
+
+    fn supports_tools(&self) -> bool {
+        true
+    }
+
+    fn complete_with_tools(&self, system: &str, user: &str, tools: &[ToolSpec]) -> AiResult {
+        call_openai_shaped_with_tools(
+            &self.base_url,
+            Some(&self.api_key),
+            &self.model,
+            self.temperature,
+            self.max_tokens,
+            system,
+            user,
+            tools,
+        )
+    }
+
+    fn complete_stream(&self, system: &str, user: &str, on_token: &mut dyn FnMut(&str)) -> AiResult {
+        call_openai_shaped_stream(
+            &self.base_url,
+            Some(&self.api_key),
+            &self.model,
+            self.temperature,
+            self.max_tokens,
+            system,
+            user,
+            on_token,
+        )
+    }
+}
+
+/// `ChatBackend` for DeepSeek's OpenAI-shaped `chat/completions` API.
+pub struct DeepSeekBackend {
+    pub model: String,
+    pub base_url: String,
+    pub api_key: String,
+    pub temperature: f32,
+    pub max_tokens: u32,
+}
+
+impl DeepSeekBackend {
+    pub fn new(model: impl Into<String>) -> Result<Self, String> {
+        let api_key = std::env::var("DEEPSEEK_API_KEY").map_err(|_| {
+            "Missing DEEPSEEK_API_KEY. Get one at platform.deepseek.com, then run:\n  export DEEPSEEK_API_KEY=\"your-key\"".to_string()
+        })?;
+        Ok(Self {
+            model: model.into(),
+            base_url: "https://api.deepseek.com/chat/completions".to_string(),
+            api_key,
+            temperature: 0.1,
+            max_tokens: 2000,
+        })
+    }
+}
+
+impl ChatBackend for DeepSeekBackend {
+    fn complete(&self, system: &str, user: &str) -> AiResult {
+        call_openai_shaped(
+            &self.base_url,
+            Some(&self.api_key),
+            &self.model,
+            self.temperature,
+            self.max_tokens,
+            system,
+            user,
+        )
+    }
+
+    fn supports_tools(&self) -> bool {
+        true
+    }
+
+    fn complete_with_tools(&self, system: &str, user: &str, tools: &[ToolSpec]) -> AiResult {
+        call_openai_shaped_with_tools(
+            &self.base_url,
+            Some(&self.api_key),
+            &self.model,
+            self.temperature,
+            self.max_tokens,
+            system,
+            user,
+            tools,
+        )
+    }
+
+    fn complete_stream(&self, system: &str, user: &str, on_token: &mut dyn FnMut(&str)) -> AiResult {
+        call_openai_shaped_stream(
+            &self.base_url,
+            Some(&self.api_key),
+            &self.model,
+            self.temperature,
+            self.max_tokens,
+            system,
+            user,
+            on_token,
+        )
+    }
+}
+
+/// `ChatBackend` for Anthropic's Messages API.
+pub struct AnthropicBackend {
+    pub model: String,
+    pub base_url: String,
+    pub api_key: String,
+    pub max_tokens: u32,
+}
+
+impl AnthropicBackend {
+    pub fn new(model: impl Into<String>) -> Result<Self, String> {
+        let api_key = std::env::var("ANTHROPIC_API_KEY").map_err(|_| {
+            "Missing ANTHROPIC_API_KEY. Get one at console.anthropic.com, then run:\n  export ANTHROPIC_API_KEY=\"your-key\"".to_string()
+        })?;
+        Ok(Self {
+            model: model.into(),
+            base_url: "https://api.anthropic.com/v1/messages".to_string(),
+            api_key,
+            max_tokens: 2000,
+        })
+    }
+}
+
+impl ChatBackend for AnthropicBackend {
+    fn complete(&self, system: &str, user: &str) -> AiResult {
+        call_claude_shaped(&self.base_url, &self.api_key, &self.model, self.max_tokens, system, user)
+    }
+
+    fn supports_tools(&self) -> bool {
+        true
+    }
+
+    fn complete_with_tools(&self, system: &str, user: &str, tools: &[ToolSpec]) -> AiResult {
+        call_claude_shaped_with_tools(
+            &self.base_url,
+            &self.api_key,
+            &self.model,
+            self.max_tokens,
+            system,
+            user,
+            tools,
+        )
+    }
+
+    fn complete_stream(&self, system: &str, user: &str, on_token: &mut dyn FnMut(&str)) -> AiResult {
+        call_claude_shaped_stream(&self.base_url, &self.api_key, &self.model, self.max_tokens, system, user, on_token)
+    }
+}
+
+/// `ChatBackend` for a local, OpenAI-compatible server (Ollama, llama.cpp,
+/// ...) — no API key required. `new` reads the endpoint from
+/// `LOCAL_ENDPOINT`, falling back to `DEFAULT_LOCAL_ENDPOINT`.
+pub struct LocalBackend {
+    pub model: String,
+    pub base_url: String,
+    pub temperature: f32,
+    pub max_tokens: u32,
+}
+
+impl LocalBackend {
+    pub fn new(model: impl Into<String>) -> Self {
+        Self {
+            model: model.into(),
+            base_url: std::env::var("LOCAL_ENDPOINT").unwrap_or_else(|_| DEFAULT_LOCAL_ENDPOINT.to_string()),
+            temperature: 0.2,
+            max_tokens: 2000,
+        }
+    }
+}
+
+impl ChatBackend for LocalBackend {
+    fn complete(&self, system: &str, user: &str) -> AiResult {
+        call_openai_shaped(
+            &self.base_url,
+            None,
+            &self.model,
+            self.temperature,
+            self.max_tokens,
+            system,
+            user,
+        )
+    }
+
+    fn complete_stream(&self, system: &str, user: &str, on_token: &mut dyn FnMut(&str)) -> AiResult {
+        call_openai_shaped_stream(
+            &self.base_url,
+            None,
+            &self.model,
+            self.temperature,
+            self.max_tokens,
+            system,
+            user,
+            on_token,
+        )
+    }
+}
+
+/// Serde-deserializable description of one backend — lets users add
+/// providers like Gemini or Cohere by declaring a config entry rather than
+/// editing code (once a matching `ChatBackend` impl exists). `provider`
+/// selects which concrete backend `Backend::from_config` builds; the rest
+/// override that provider's usual defaults when present.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendConfig {
+    pub provider: Backend,
+    pub model: String,
+    #[serde(default)]
+    pub base_url: Option<String>,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+}
+
+impl Backend {
+    /// Builds the concrete `ChatBackend` named by `config.provider`,
+    /// applying whichever of `config`'s `base_url`/`temperature`/
+    /// `max_tokens` are present over that provider's usual defaults.
+    /// Reads that provider's API key from its usual environment variable
+    /// (`Backend::Local` needs none).
+    pub fn from_config(config: &BackendConfig) -> Result<Box<dyn ChatBackend>, String> {
+        match config.provider {
+            Backend::OpenAi => {
+                let mut backend = OpenAiBackend::new(config.model.clone())?;
+                apply_overrides(&mut backend.base_url, &mut backend.temperature, &mut backend.max_tokens, config);
+                Ok(Box::new(backend))
+            }
+            Backend::DeepSeek => {
+                let mut backend = DeepSeekBackend::new(config.model.clone())?;
+                apply_overrides(&mut backend.base_url, &mut backend.temperature, &mut backend.max_tokens, config);
+                Ok(Box::new(backend))
+            }
+            Backend::Anthropic => {
+                let mut backend = AnthropicBackend::new(config.model.clone())?;
+                if let Some(base_url) = &config.base_url {
+                    backend.base_url = base_url.clone();
+                }
+                if let Some(max_tokens) = config.max_tokens {
+                    backend.max_tokens = max_tokens;
+                }
+                Ok(Box::new(backend))
+            }
+            Backend::Local => {
+                let mut backend = LocalBackend::new(config.model.clone());
+                apply_overrides(&mut backend.base_url, &mut backend.temperature, &mut backend.max_tokens, config);
+                Ok(Box::new(backend))
+            }
+        }
+    }
+}
+
+/// Applies whichever of `config`'s `base_url`/`temperature`/`max_tokens`
+/// are present onto a backend's matching fields, shared by the three
+/// `Backend::from_config` arms whose backends expose all three knobs.
+fn apply_overrides(
+    base_url: &mut String,
+    temperature: &mut f32,
+    max_tokens: &mut u32,
+    config: &BackendConfig,
+) {
+    if let Some(url) = &config.base_url {
+        *base_url = url.clone();
+    }
+    if let Some(t) = config.temperature {
+        *temperature = t;
+    }
+    if let Some(m) = config.max_tokens {
+        *max_tokens = m;
+    }
+}
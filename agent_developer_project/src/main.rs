@@ -20,9 +20,13 @@
 mod agents;
 mod messages;
 mod pipeline;
+mod repl;
+mod sandbox;
+mod spec_parser;
 mod task;
 
 use pipeline::Pipeline;
+use repl::Repl;
 use std::io::{self, BufRead, Write};
 
 fn main() {
@@ -31,9 +35,68 @@ fn main() {
     println!("║      Built in Rust                   ║");
     println!("╚══════════════════════════════════════╝");
 
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|a| a == "--dry-run") {
+        dry_run_loop();
+        return;
+    }
+    if args.iter().any(|a| a == "--repl") {
+        Repl::new().run();
+        return;
+    }
+
     interactive_loop();
 }
 
+/// Dry-run mode: same prompt loop as `interactive_loop`, but previews the plan
+/// via `Pipeline::simulate` instead of actually compiling and running anything.
+/// Lets users inspect the pipeline on a machine without a Rust toolchain.
+fn dry_run_loop() {
+    let pipeline = Pipeline::new();
+
+    println!("\n╔══════════════════════════════════════╗");
+    println!("║           DRY-RUN MODE ACTIVE        ║");
+    println!("╠══════════════════════════════════════╣");
+    println!("║  Type a coding task and press Enter  ║");
+    println!("║  The plan will be previewed only —   ║");
+    println!("║  nothing is compiled or executed.    ║");
+    println!("║  Type \"exit\" or \"quit\" to stop.      ║");
+    println!("╚══════════════════════════════════════╝\n");
+
+    let stdin = io::stdin();
+
+    loop {
+        print!("Your task > ");
+        io::stdout().flush().expect("Failed to flush stdout");
+
+        let mut input = String::new();
+        match stdin.lock().read_line(&mut input) {
+            Ok(0) => {
+                println!("\n[AGENT TEAM] Input stream closed. Goodbye!");
+                break;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                println!("[AGENT TEAM] Error reading input: {}", e);
+                break;
+            }
+        }
+
+        let task = input.trim();
+
+        if task.is_empty() {
+            continue;
+        }
+
+        if task.eq_ignore_ascii_case("exit") || task.eq_ignore_ascii_case("quit") {
+            println!("\n[AGENT TEAM] Goodbye! Thanks for using Agent Team.");
+            break;
+        }
+
+        pipeline.simulate(task);
+    }
+}
+
 /// Interactive mode: waits for the user to type a task, runs the full
 /// agent pipeline on it, then asks for the next task.
 /// Type "exit" or "quit" to stop.
@@ -53,6 +53,9 @@ pub struct FinalPayload {
     pub task_id: u32,
     pub code: String,
     pub summary: String,
+    /// Human-readable description of each `FixRule` that actually fired,
+    /// in the order the Debugger's rule registry applied them.
+    pub fixes_applied: Vec<String>,
 }
 
 /// The validator's output: did the final code match the user's task?
@@ -62,4 +65,58 @@ pub struct ValidationPayload {
     pub task_id: u32,
     pub passed: bool,
     pub reason: String,
+    /// Structured compile/run diagnostics from the Validator's `rustc` invocation,
+    /// fed back into the next retry so the Coder/Debugger see real errors.
+    pub run_result: Option<RunResult>,
+    /// Per-test-case pass/fail results from the generated `#[test]` harness
+    /// (case label, passed).
+    pub test_case_results: Vec<(String, bool)>,
+}
+
+/// Structural facts about generated code extracted from a real `syn` AST rather
+/// than substring scanning. Built and read by the Validator
+/// (`ValidatorAgent::structural_report`); the Reviewer's AST brain parses the
+/// code itself and does not use this report.
+#[derive(Debug, Clone, Default)]
+pub struct StructuralReport {
+    /// Names of every top-level function definition found in the AST.
+    pub functions: Vec<String>,
+    /// Whether a genuine `fn main` item exists (not just the substring "fn main()").
+    pub has_main: bool,
+    /// Whether `syn::parse_file` succeeded at all.
+    pub parse_ok: bool,
+}
+
+/// Structured result of compiling and running the candidate code.
+/// Lets the retry loop hand the Coder/Debugger the actual `rustc` diagnostics
+/// instead of a restated task description.
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct RunResult {
+    pub run_started: std::time::SystemTime,
+    pub duration: std::time::Duration,
+    pub return_code: i32,
+    pub stdout: Option<String>,
+    pub stderr: Option<String>,
+    pub compile_error: Option<String>,
+    /// Whether this run completed normally or had to be forcibly cut off by
+    /// the sandbox (timeout, output flood), so callers can react differently
+    /// than to an ordinary non-zero exit or compile error.
+    pub outcome: ExecOutcome,
+}
+
+/// Distinguishes a normal completed compile/run from one the sandbox had to
+/// forcibly cut off. Surfaced through `RunResult`/`ValidationPayload` so the
+/// retry loop can tell "the code is wrong" apart from "the code hung" or
+/// "the code flooded its output" and react accordingly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecOutcome {
+    /// Ran to completion within the timeout and output budget.
+    Completed,
+    /// Exceeded the wall-clock timeout and was killed.
+    TimedOut,
+    /// Captured stdout/stderr hit the byte cap and was killed to stop the flood.
+    OutputTruncated,
+    /// Killed for a reason other than the two above (e.g. spawn failure).
+    Killed,
 }
@@ -4,6 +4,7 @@ use crate::agents::debugger::DebuggerAgent;
 use crate::agents::planner::PlannerAgent;
 use crate::agents::reviewer::ReviewerAgent;
 use crate::agents::validator::ValidatorAgent;
+use crate::messages::{ExecOutcome, RunResult};
 use crate::task::TaskStatus;
 use crate::thinking::{ThinkingTimer, ProcessingStage};
 
@@ -57,6 +58,7 @@ impl Pipeline {
 
         let mut attempt = 0u32;
         let mut enriched_description = task_description.to_string();
+        let mut last_run_result = None;
 
         loop {
             attempt += 1;
@@ -77,6 +79,42 @@ impl Pipeline {
                     "IMPORTANT: Previous attempt did not address '{}'. Make sure the function name and logic relate directly to this task.",
                     enriched_description
                 ));
+
+                // Feed the actual rustc diagnostics from the last attempt back in,
+                // instead of just a restated task description, so the Coder sees
+                // concrete compile/run errors (mismatched types, unresolved names, etc.).
+                if let Some(RunResult { compile_error, stderr, outcome, .. }) = &last_run_result {
+                    match outcome {
+                        ExecOutcome::TimedOut => {
+                            retry_plan.steps.push(
+                                "IMPORTANT: The previous attempt timed out instead of compiling cleanly — \
+                                 it likely hangs (infinite loop, blocking read, or unbounded recursion). \
+                                 Rewrite it to guarantee termination."
+                                    .to_string(),
+                            );
+                        }
+                        ExecOutcome::OutputTruncated => {
+                            retry_plan.steps.push(
+                                "IMPORTANT: The previous attempt's output was cut off for exceeding the \
+                                 output size cap — it likely prints in an unbounded loop. Bound the output."
+                                    .to_string(),
+                            );
+                        }
+                        ExecOutcome::Killed | ExecOutcome::Completed => {
+                            if let Some(compile_error) = compile_error {
+                                retry_plan.steps.push(format!(
+                                    "COMPILER ERROR from previous attempt:\n{}",
+                                    compile_error
+                                ));
+                            } else if let Some(stderr) = stderr {
+                                retry_plan.steps.push(format!(
+                                    "RUNTIME STDERR from previous attempt:\n{}",
+                                    stderr
+                                ));
+                            }
+                        }
+                    }
+                }
             }
             let code = self.coder.process_with_task(retry_plan, &enriched_description);
 
@@ -100,6 +138,7 @@ impl Pipeline {
 
             // Stage 6: Validator checks if output matches the task
             let validation = self.validator.process(&final_result, &enriched_description);
+            last_run_result = validation.run_result.clone();
 
             if validation.passed || attempt >= MAX_RETRIES {
                 if !validation.passed {
@@ -117,4 +156,51 @@ impl Pipeline {
             enriched_description = format!("{} (focus on: {})", task_description, enriched_description);
         }
     }
+
+    /// Walks the same Coordinator→Planner→Coder→Reviewer→Debugger→Validator sequence
+    /// as `run`, but only prints what each stage *would* do — no `ThinkingTimer`
+    /// delays, no `rustc` invocation, no temp files. Lets a user preview the plan and
+    /// the exact validation command on a machine without a Rust toolchain installed.
+    pub fn simulate(&self, task_description: &str) {
+        println!("\n  \x1b[1;35m[PIPELINE]\x1b[0m \x1b[1mDry run — no code will be compiled or executed.\x1b[0m");
+
+        println!("\n\x1b[1;32m[COORDINATOR]\x1b[0m Would assign task: \"{}\"", task_description);
+        println!("\x1b[1;32m[COORDINATOR]\x1b[0m Would dispatch to Planner...");
+
+        println!("\n\x1b[1;36m[PLANNER]\x1b[0m Would extract requirements:");
+        let requirements = self.planner.extract_requirements(task_description);
+        let requirement_lines = requirements.to_display_lines();
+        for (i, req) in requirement_lines.iter().enumerate() {
+            println!("\x1b[1;36m[PLANNER]\x1b[0m   {}. {}", i + 1, req);
+        }
+        println!("\x1b[1;36m[PLANNER]\x1b[0m Would break the task into steps:");
+        let steps = self.planner.generate_steps(task_description, &requirements);
+        for (i, step) in steps.iter().enumerate() {
+            println!("\x1b[1;36m[PLANNER]\x1b[0m   Step {}: {}", i + 1, step);
+        }
+
+        println!(
+            "\n\x1b[1;34m[CODER]\x1b[0m Would draft code in 3 passes (outline, draft, refinement) addressing the plan above."
+        );
+
+        println!("\n\x1b[1;35m[REVIEWER]\x1b[0m Would statically review the drafted code for issues.");
+
+        println!("\n\x1b[1;31m[DEBUGGER]\x1b[0m Would apply fixes for any issues the Reviewer reported.");
+
+        println!("\n\x1b[1;33m[VALIDATOR]\x1b[0m Would generate test cases:");
+        let test_cases = self.validator.generate_test_cases(task_description);
+        for (i, (input, expected)) in test_cases.iter().enumerate() {
+            println!("\x1b[1;33m[VALIDATOR]\x1b[0m   Test {}: {} → expect: {}", i + 1, input, expected);
+        }
+        println!("\x1b[1;33m[VALIDATOR]\x1b[0m Would compile the candidate with:");
+        println!("\x1b[1;33m[VALIDATOR]\x1b[0m   rustc <tmp>/agent_validator_<unique>/candidate.rs -o <tmp>/agent_validator_<unique>/candidate");
+        println!(
+            "\x1b[1;33m[VALIDATOR]\x1b[0m Then run the binary, and for any test case with a concrete expected value, compile a #[test] harness with:"
+        );
+        println!("\x1b[1;33m[VALIDATOR]\x1b[0m   rustc --test <tmp>/agent_validator_<unique>/harness.rs -o <tmp>/agent_validator_<unique>/harness");
+
+        println!(
+            "\n  \x1b[1;35m[PIPELINE]\x1b[0m Dry run complete. Re-run without --dry-run to actually build and execute."
+        );
+    }
 }
@@ -0,0 +1,236 @@
+use crate::agents::coder::CoderAgent;
+use crate::agents::debugger::DebuggerAgent;
+use crate::agents::planner::PlannerAgent;
+use crate::agents::reviewer::ReviewerAgent;
+use crate::messages::{FinalPayload, PlanPayload, TaskPayload};
+use std::io::{self, BufRead, Write};
+
+/// One completed turn, kept in session history so its result can be
+/// re-inspected via `:last` or fed back into the chain via `:replan <id>`.
+struct Turn {
+    id: u32,
+    description: String,
+    plan: PlanPayload,
+    result: FinalPayload,
+}
+
+/// Drives the Planner→Coder→Reviewer→Debugger chain interactively instead of
+/// one task per process invocation. Reads multiline task descriptions from
+/// stdin and keeps a session history so past results stay reachable without
+/// retyping the task.
+pub struct Repl {
+    planner: PlannerAgent,
+    coder: CoderAgent,
+    reviewer: ReviewerAgent,
+    debugger: DebuggerAgent,
+    history: Vec<Turn>,
+    next_task_id: u32,
+}
+
+impl Repl {
+    pub fn new() -> Self {
+        Repl {
+            planner: PlannerAgent::new(),
+            coder: CoderAgent::new(),
+            reviewer: ReviewerAgent::new(),
+            debugger: DebuggerAgent::new(),
+            history: Vec::new(),
+            next_task_id: 1,
+        }
+    }
+
+    /// Runs the REPL until stdin closes or the user types `:quit`.
+    pub fn run(&mut self) {
+        println!("\n╔══════════════════════════════════════╗");
+        println!("║             REPL MODE ACTIVE         ║");
+        println!("╠══════════════════════════════════════╣");
+        println!("║  Type a task, or end a line with \\   ║");
+        println!("║  (or use a ``` fenced block) to keep ║");
+        println!("║  typing across multiple lines.       ║");
+        println!("║  :help for commands, :quit to stop.  ║");
+        println!("╚══════════════════════════════════════╝\n");
+
+        let stdin = io::stdin();
+
+        loop {
+            print!("repl> ");
+            io::stdout().flush().expect("Failed to flush stdout");
+
+            let Some(entry) = Self::read_entry(&stdin) else {
+                println!("\n[REPL] Input stream closed. Goodbye!");
+                break;
+            };
+
+            let trimmed = entry.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            if trimmed.eq_ignore_ascii_case(":quit")
+                || trimmed.eq_ignore_ascii_case("exit")
+                || trimmed.eq_ignore_ascii_case("quit")
+            {
+                println!("[REPL] Goodbye! Thanks for using Agent Team.");
+                break;
+            }
+
+            if trimmed.eq_ignore_ascii_case(":help") {
+                Self::print_help();
+                continue;
+            }
+
+            if trimmed.eq_ignore_ascii_case(":last") {
+                self.print_last();
+                continue;
+            }
+
+            if let Some(rest) = trimmed.strip_prefix(":replan") {
+                match rest.trim().parse::<u32>() {
+                    Ok(id) => self.replan(id),
+                    Err(_) => println!("[REPL] Usage: :replan <id>"),
+                }
+                continue;
+            }
+
+            if trimmed.starts_with(':') {
+                println!("[REPL] Unknown command '{}'. Type :help for a list.", trimmed);
+                continue;
+            }
+
+            self.process_task(entry);
+        }
+    }
+
+    /// Reads one logical entry from stdin. A line ending in `\` keeps
+    /// accumulating until a blank line; a ` ``` ` fence keeps accumulating
+    /// until the matching closing fence. Returns `None` on EOF with nothing
+    /// buffered yet.
+    fn read_entry(stdin: &io::Stdin) -> Option<String> {
+        let mut buffer = String::new();
+        let mut fenced = false;
+        let mut first_line = true;
+        let mut continuing = false;
+
+        loop {
+            let mut raw = String::new();
+            let read = stdin.lock().read_line(&mut raw);
+            if matches!(read, Ok(0)) || read.is_err() {
+                return if buffer.is_empty() { None } else { Some(buffer) };
+            }
+
+            let line = raw.trim_end_matches(['\n', '\r']);
+
+            if line.trim() == "```" {
+                if !buffer.is_empty() {
+                    buffer.push('\n');
+                }
+                buffer.push_str(line);
+                fenced = !fenced;
+                if !fenced {
+                    return Some(buffer);
+                }
+                first_line = false;
+                continue;
+            }
+
+            if fenced {
+                if !buffer.is_empty() {
+                    buffer.push('\n');
+                }
+                buffer.push_str(line);
+                continue;
+            }
+
+            if line.is_empty() && !first_line {
+                return Some(buffer);
+            }
+
+            let continues = line.ends_with('\\');
+            let content = if continues { &line[..line.len() - 1] } else { line };
+
+            if !buffer.is_empty() {
+                buffer.push('\n');
+            }
+            buffer.push_str(content);
+            first_line = false;
+            continuing = continuing || continues;
+
+            if !continuing {
+                return Some(buffer);
+            }
+        }
+    }
+
+    /// Runs one task through the Planner→Coder→Reviewer→Debugger chain and
+    /// records the turn in session history.
+    fn process_task(&mut self, description: String) {
+        let task_id = self.next_task_id;
+        self.next_task_id += 1;
+
+        let plan = self.planner.process(TaskPayload {
+            task_id,
+            description: description.clone(),
+        });
+        let result = self.run_chain(plan.clone(), &description);
+
+        println!("\n[REPL] Task #{} complete.\n{}", task_id, result.code);
+        println!("[REPL] Summary: {}", result.summary);
+
+        self.history.push(Turn {
+            id: task_id,
+            description,
+            plan,
+            result,
+        });
+    }
+
+    /// Feeds an already-generated plan through Coder→Reviewer→Debugger.
+    fn run_chain(&mut self, plan: PlanPayload, description: &str) -> FinalPayload {
+        let code = self.coder.process_with_task(plan, description);
+        let review = self.reviewer.process(code);
+        self.debugger.process(review)
+    }
+
+    /// `:last` — reprints the most recently produced code and summary.
+    fn print_last(&self) {
+        match self.history.last() {
+            Some(turn) => {
+                println!("\n[REPL] Last result — task #{} (\"{}\"):", turn.id, turn.description);
+                println!("{}", turn.result.code);
+                println!("[REPL] Summary: {}", turn.result.summary);
+            }
+            None => println!("[REPL] No task has been run yet this session."),
+        }
+    }
+
+    /// `:replan <id>` — re-runs the Coder→Reviewer→Debugger chain against the
+    /// stored plan for a previous task, without re-asking the Planner.
+    fn replan(&mut self, id: u32) {
+        let Some(turn) = self.history.iter().find(|t| t.id == id) else {
+            println!("[REPL] No task #{} in this session.", id);
+            return;
+        };
+        let plan = turn.plan.clone();
+        let description = turn.description.clone();
+
+        println!("[REPL] Replanning task #{}...", id);
+        let result = self.run_chain(plan, &description);
+
+        println!("\n[REPL] Task #{} re-run complete.\n{}", id, result.code);
+        println!("[REPL] Summary: {}", result.summary);
+
+        if let Some(turn) = self.history.iter_mut().find(|t| t.id == id) {
+            turn.result = result;
+        }
+    }
+
+    fn print_help() {
+        println!("\n[REPL] Commands:");
+        println!("  :help           Show this message");
+        println!("  :last           Show the most recently produced code");
+        println!("  :replan <id>    Re-run Coder→Reviewer→Debugger for task <id>");
+        println!("  :quit           Exit the REPL");
+        println!("\n[REPL] Anything else is treated as a new task description.");
+        println!("[REPL] End a line with \\ or use a ``` fenced block to enter multiple lines.\n");
+    }
+}
@@ -0,0 +1,181 @@
+// ============================================================
+// sandbox.rs: Bounded, cleaned-up execution of compiled candidate code
+// ============================================================
+//
+// The Validator compiles and runs arbitrary generated code. Left unbounded, a
+// generated infinite loop would hang the whole pipeline, and a program that
+// floods stdout could exhaust memory via unbounded capture. This module gives
+// every compile/run invocation a wall-clock timeout, a captured-output byte
+// cap, and a unique scratch directory that's removed even on an early return.
+
+use crate::messages::ExecOutcome;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Wall-clock budget for a single compile or run invocation.
+pub const EXEC_TIMEOUT: Duration = Duration::from_secs(5);
+/// Maximum bytes captured from stdout/stderr each before the stream is cut off.
+pub const OUTPUT_CAP_BYTES: usize = 64 * 1024;
+
+/// RAII guard for a unique scratch directory under `std::env::temp_dir()`.
+/// The directory — source file, compiled binary, and anything else `rustc`
+/// drops there — is removed on drop, including on an early return from a
+/// timeout or spawn failure.
+pub struct ScratchDir {
+    path: PathBuf,
+}
+
+impl ScratchDir {
+    /// Creates a fresh scratch directory with a randomized, unique name.
+    pub fn new() -> std::io::Result<Self> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let random = {
+            use std::hash::{BuildHasher, Hasher};
+            std::collections::hash_map::RandomState::new().build_hasher().finish()
+        };
+        let unique = format!("agent_validator_{}_{}_{:016x}", std::process::id(), nanos, random);
+        let path = std::env::temp_dir().join(unique);
+        std::fs::create_dir_all(&path)?;
+        Ok(ScratchDir { path })
+    }
+
+    /// Path to a file named `name` inside the scratch directory.
+    pub fn join(&self, name: &str) -> PathBuf {
+        self.path.join(name)
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Result of a bounded process invocation.
+pub struct BoundedOutput {
+    pub outcome: ExecOutcome,
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Spawns `cmd` with a wall-clock timeout and a captured-output byte cap.
+/// Kills the child and reports `ExecOutcome::TimedOut` if it outlives
+/// `timeout`; kills it and reports `ExecOutcome::OutputTruncated` if either
+/// stream exceeds `cap` bytes before the process exits or times out.
+pub fn run_bounded(mut cmd: Command, timeout: Duration, cap: usize) -> BoundedOutput {
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            return BoundedOutput {
+                outcome: ExecOutcome::Killed,
+                exit_code: None,
+                stdout: String::new(),
+                stderr: format!("Failed to spawn process: {}", e),
+            };
+        }
+    };
+
+    let stdout_capped = Arc::new(AtomicBool::new(false));
+    let stderr_capped = Arc::new(AtomicBool::new(false));
+
+    let stdout_reader = child
+        .stdout
+        .take()
+        .map(|s| spawn_capped_reader(s, cap, stdout_capped.clone()));
+    let stderr_reader = child
+        .stderr
+        .take()
+        .map(|s| spawn_capped_reader(s, cap, stderr_capped.clone()));
+
+    let deadline = Instant::now() + timeout;
+    let mut timed_out = false;
+    let mut cap_exceeded = false;
+
+    let exit_status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) => {
+                if stdout_capped.load(Ordering::Relaxed) || stderr_capped.load(Ordering::Relaxed) {
+                    cap_exceeded = true;
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    break None;
+                }
+                if Instant::now() >= deadline {
+                    timed_out = true;
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    break None;
+                }
+                thread::sleep(Duration::from_millis(25));
+            }
+            Err(_) => break None,
+        }
+    };
+
+    let stdout = stdout_reader.map(|h| h.join().unwrap_or_default()).unwrap_or_default();
+    let stderr = stderr_reader.map(|h| h.join().unwrap_or_default()).unwrap_or_default();
+
+    let outcome = if cap_exceeded {
+        ExecOutcome::OutputTruncated
+    } else if timed_out {
+        ExecOutcome::TimedOut
+    } else if exit_status.is_none() {
+        ExecOutcome::Killed
+    } else {
+        ExecOutcome::Completed
+    };
+
+    BoundedOutput {
+        outcome,
+        exit_code: exit_status.and_then(|s| s.code()),
+        stdout,
+        stderr,
+    }
+}
+
+/// Reads `reader` to completion on a background thread, capping the captured
+/// bytes at `cap` and flipping `capped` the moment that cap is crossed. Keeps
+/// draining past the cap (without storing the data) so the child can't block
+/// forever writing into a full pipe while the caller decides whether to kill it.
+fn spawn_capped_reader<R: Read + Send + 'static>(
+    mut reader: R,
+    cap: usize,
+    capped: Arc<AtomicBool>,
+) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut buf = Vec::with_capacity(cap.min(4096));
+        let mut chunk = [0u8; 4096];
+
+        loop {
+            match reader.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(n) => {
+                    if buf.len() < cap {
+                        let take = (cap - buf.len()).min(n);
+                        buf.extend_from_slice(&chunk[..take]);
+                        if take < n {
+                            capped.store(true, Ordering::Relaxed);
+                        }
+                    } else {
+                        capped.store(true, Ordering::Relaxed);
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+
+        String::from_utf8_lossy(&buf).to_string()
+    })
+}
@@ -0,0 +1,269 @@
+//! A small hand-rolled parser-combinator grammar for task-spec mini-language,
+//! in the style of crates like `chumsky` (there's no dependency manager in
+//! this tree to pull one in, so the combinators below are written from
+//! scratch). Recognizes clauses like `takes <type-expr>`, `returns
+//! <type-expr>`, `must be O(<complexity>)`, `handles <edge-case-list>`, and
+//! `uses <technique>`, and produces a typed [`Requirements`] instead of the
+//! free-form strings the old keyword scanner returned.
+
+/// A parsed type expression, kept as the raw Rust-ish token(s) it was
+/// written as (e.g. `Vec<i32>`, `&str`, `Option<usize>`).
+pub type TypeExpr = String;
+
+/// Structured requirements extracted from a task description. Any clause
+/// that doesn't fit the grammar is preserved verbatim in `freeform` rather
+/// than silently dropped.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Requirements {
+    pub inputs: Vec<TypeExpr>,
+    pub outputs: Vec<TypeExpr>,
+    pub complexity: Option<String>,
+    pub edge_cases: Vec<String>,
+    pub techniques: Vec<String>,
+    /// Prose that didn't match any recognized clause.
+    pub freeform: Vec<String>,
+}
+
+impl Requirements {
+    /// Flattens the structured fields into the display-string format the
+    /// rest of the pipeline (console output, plan steps) already expects.
+    pub fn to_display_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for input in &self.inputs {
+            lines.push(format!("Input: {}", input));
+        }
+        for output in &self.outputs {
+            lines.push(format!("Output: {}", output));
+        }
+        if let Some(complexity) = &self.complexity {
+            lines.push(format!("Performance constraint: {}", complexity));
+        }
+        for edge_case in &self.edge_cases {
+            lines.push(format!("Handle edge case: {}", edge_case));
+        }
+        for technique in &self.techniques {
+            lines.push(format!("Use technique: {}", technique));
+        }
+        for note in &self.freeform {
+            lines.push(format!("Note: {}", note));
+        }
+        if lines.is_empty() {
+            lines.push("Process the input meaningfully".to_string());
+            lines.push("Return a valid result".to_string());
+            lines.push("Handle edge cases (empty/null inputs)".to_string());
+        }
+        lines
+    }
+}
+
+/// Case-insensitively finds `needle` (assumed ASCII) in `haystack`,
+/// returning a byte offset valid for slicing the original `haystack`.
+/// Walks `haystack`'s own characters directly instead of lowercasing the
+/// whole string first — `str::to_lowercase` can change a character's byte
+/// length (e.g. `İ` becomes the two-character `i̇`), which drifts any
+/// offset found in the lowercased copy out of sync with the original.
+fn find_ci(haystack: &str, needle: &str) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+
+    let needle_chars: Vec<char> = needle.chars().collect();
+    let haystack_chars: Vec<(usize, char)> = haystack.char_indices().collect();
+
+    for start in 0..haystack_chars.len() {
+        if start + needle_chars.len() > haystack_chars.len() {
+            break;
+        }
+        let matches = needle_chars
+            .iter()
+            .enumerate()
+            .all(|(offset, nc)| haystack_chars[start + offset].1.eq_ignore_ascii_case(nc));
+        if matches {
+            return Some(haystack_chars[start].0);
+        }
+    }
+
+    None
+}
+
+/// Parses a single Rust-ish type expression starting at `input` (leading
+/// whitespace is skipped). Consumes one token, plus a second token when the
+/// first is `&mut`, so `&str`, `Vec<i32>`, `Option<usize>`, and `&mut Vec<i32>`
+/// all parse as one type-expr. Returns the parsed text and the remaining
+/// input, or `None` if `input` has nothing left to consume.
+fn type_expr(input: &str) -> Option<(TypeExpr, &str)> {
+    let trimmed = input.trim_start();
+    let first_end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+    let (first_tok, mut rest) = trimmed.split_at(first_end);
+    let first_tok = first_tok.trim_end_matches([',', '.']);
+    if first_tok.is_empty() {
+        return None;
+    }
+
+    let mut parsed = first_tok.to_string();
+    if first_tok.eq_ignore_ascii_case("&mut") {
+        let rest_trimmed = rest.trim_start();
+        let second_end = rest_trimmed.find(char::is_whitespace).unwrap_or(rest_trimmed.len());
+        let (second_tok, after_second) = rest_trimmed.split_at(second_end);
+        let second_tok = second_tok.trim_end_matches([',', '.']);
+        if !second_tok.is_empty() {
+            parsed.push(' ');
+            parsed.push_str(second_tok);
+            rest = after_second;
+        }
+    }
+
+    Some((parsed, rest))
+}
+
+/// Matches a `keyword <type-expr>` clause anywhere in `description`, trying
+/// each keyword in turn and returning the parsed type plus the byte range
+/// the clause occupied (so the caller can exclude it from the freeform
+/// leftover).
+fn type_clause(description: &str, keywords: &[&str]) -> Option<(TypeExpr, (usize, usize))> {
+    for keyword in keywords {
+        let Some(start) = find_ci(description, keyword) else { continue };
+        let after_keyword = start + keyword.len();
+        if let Some((ty, rest)) = type_expr(&description[after_keyword..]) {
+            let end = description.len() - rest.len();
+            return Some((ty, (start, end)));
+        }
+    }
+    None
+}
+
+/// Matches `must be O(<complexity>)`, returning the complexity text (without
+/// the surrounding parens) and the clause's byte range.
+fn complexity_clause(description: &str) -> Option<(String, (usize, usize))> {
+    let marker = "must be o(";
+    let start = find_ci(description, marker)?;
+    let after_marker = start + marker.len();
+    let close_rel = description[after_marker..].find(')')?;
+    let complexity = description[after_marker..after_marker + close_rel].trim().to_string();
+    let end = after_marker + close_rel + 1;
+    Some((format!("O({})", complexity), (start, end)))
+}
+
+/// Matches `handles <comma/and-separated list>` or `uses <comma/and-separated
+/// list>`, stopping at a sentence boundary (`.`, `;`) or the next recognized
+/// clause keyword. Splits the list on `,` and `and`.
+fn list_clause(description: &str, keyword: &str) -> Option<(Vec<String>, (usize, usize))> {
+    let start = find_ci(description, keyword)?;
+    let after_keyword = start + keyword.len();
+    let tail = &description[after_keyword..];
+
+    let stop_markers = ["takes", "accepts", "given", "returns", "produces", "handles", "uses", "must be o("];
+    let mut stop_at = tail.find(['.', ';']).unwrap_or(tail.len());
+    for marker in stop_markers {
+        if let Some(pos) = find_ci(tail, marker) {
+            if pos > 0 && pos < stop_at {
+                stop_at = pos;
+            }
+        }
+    }
+
+    let list_text = tail[..stop_at].trim();
+    if list_text.is_empty() {
+        return None;
+    }
+
+    let items: Vec<String> = list_text
+        .split(',')
+        .flat_map(|chunk| chunk.split(" and "))
+        .map(|item| item.trim().trim_end_matches('.').to_string())
+        .filter(|item| !item.is_empty())
+        .collect();
+
+    if items.is_empty() {
+        return None;
+    }
+
+    let end = after_keyword + stop_at;
+    Some((items, (start, end)))
+}
+
+/// Parses a free-form task description against the mini-spec grammar,
+/// producing a typed [`Requirements`]. Any clause that doesn't match is left
+/// in place; whatever text no clause consumed is reported back as
+/// `freeform` sentences so nothing is silently discarded.
+pub fn parse_spec(description: &str) -> Requirements {
+    let mut reqs = Requirements::default();
+    let mut consumed: Vec<(usize, usize)> = Vec::new();
+
+    if let Some((ty, span)) = type_clause(description, &["takes", "accepts", "given", "input"]) {
+        reqs.inputs.push(ty);
+        consumed.push(span);
+    }
+    if let Some((ty, span)) = type_clause(description, &["returns", "produces"]) {
+        reqs.outputs.push(ty);
+        consumed.push(span);
+    }
+    if let Some((complexity, span)) = complexity_clause(description) {
+        reqs.complexity = Some(complexity);
+        consumed.push(span);
+    }
+    if let Some((items, span)) = list_clause(description, "handles") {
+        reqs.edge_cases = items;
+        consumed.push(span);
+    }
+    if let Some((items, span)) = list_clause(description, "uses") {
+        reqs.techniques = items;
+        consumed.push(span);
+    }
+
+    reqs.freeform = leftover_sentences(description, &consumed);
+    reqs
+}
+
+/// Removes every consumed byte range from `description`, then splits
+/// whatever remains into non-trivial sentences for the `freeform` field.
+fn leftover_sentences(description: &str, consumed: &[(usize, usize)]) -> Vec<String> {
+    let mut ranges = consumed.to_vec();
+    ranges.sort_by_key(|&(start, _)| start);
+
+    let mut remaining = String::with_capacity(description.len());
+    let mut cursor = 0;
+    for (start, end) in ranges {
+        if start > cursor {
+            remaining.push_str(&description[cursor..start]);
+        }
+        cursor = cursor.max(end);
+    }
+    if cursor < description.len() {
+        remaining.push_str(&description[cursor..]);
+    }
+
+    remaining
+        .split(['.', ';'])
+        .map(|s| s.trim())
+        .filter(|s| s.len() > 3)
+        .map(|s| s.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spec_basic_clauses() {
+        let reqs = parse_spec("Takes a string and returns a number. Must be O(n log n).");
+        assert_eq!(reqs.inputs, vec!["a".to_string()]);
+        assert_eq!(reqs.outputs, vec!["a".to_string()]);
+        assert_eq!(reqs.complexity.as_deref(), Some("O(n log n)"));
+    }
+
+    #[test]
+    fn test_parse_spec_non_ascii_prefix_does_not_panic() {
+        // `İ` lowercases to the two-char `i̇`, which used to desync find_ci's
+        // byte offsets (found in a lowercased copy) from the original string.
+        let reqs = parse_spec("İstanbul takesé a string and returns a number");
+        assert_eq!(reqs.outputs, vec!["a".to_string()]);
+    }
+
+    #[test]
+    fn test_find_ci_matches_case_insensitively() {
+        assert_eq!(find_ci("Hello World", "world"), Some(6));
+        assert_eq!(find_ci("Hello World", "xyz"), None);
+    }
+}
@@ -2,7 +2,7 @@ use ggez::event::{self, EventHandler};
 use ggez::graphics::{self, Color, DrawMode, Mesh, Rect, Text};
 use ggez::{Context, GameResult};
 use rand::Rng;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 
 const WINDOW_WIDTH: f32 = 1280.0;
 const WINDOW_HEIGHT: f32 = 720.0;
@@ -13,6 +13,16 @@ enum TransactionType {
     Withdrawal(f32),
     LoanRequest(f32),
     SuspiciousActivity(String),
+    /// References a prior transaction's `tx_id` in `GameState::ledger`. Puts
+    /// that transaction's amount into the customer's `held_funds`; cannot be
+    /// approved if the referenced tx doesn't exist.
+    Dispute(u32),
+    /// References a disputed transaction's `tx_id` and releases its held
+    /// amount back into the customer's available balance.
+    Resolve(u32),
+    /// References a disputed transaction's `tx_id` and permanently reverses
+    /// the held amount out of `bank_funds`, locking the customer's account.
+    Chargeback(u32),
 }
 
 #[derive(Debug, Clone)]
@@ -23,10 +33,15 @@ struct Customer {
     transaction: TransactionType,
     is_fraudulent: bool,
     patience: f32,
+    /// Funds currently held against a disputed transaction.
+    held_funds: f32,
+    /// Set by a processed `Chargeback`; locked accounts auto-deny every
+    /// future transaction, including further disputes.
+    locked: bool,
 }
 
 impl Customer {
-    fn new_random(rng: &mut rand::rngs::ThreadRng, _day: u32) -> Self {
+    fn new_random(rng: &mut rand::rngs::ThreadRng, _day: u32, known_tx_ids: &[u32]) -> Self {
         let first_names = vec!["John", "Emma", "Michael", "Sophia", "David", "Olivia", "James", "Ava"];
         let last_names = vec!["Smith", "Johnson", "Williams", "Brown", "Jones", "Garcia", "Miller"];
 
@@ -39,15 +54,32 @@ impl Customer {
         let account_balance = rng.gen_range(100.0..50000.0);
         let credit_score = rng.gen_range(300..850);
 
-        let transaction = match rng.gen_range(0..4) {
+        let transaction = match rng.gen_range(0..7) {
             0 => TransactionType::Deposit(rng.gen_range(50.0..5000.0)),
             1 => TransactionType::Withdrawal(rng.gen_range(50.0..2000.0)),
             2 => TransactionType::LoanRequest(rng.gen_range(1000.0..50000.0)),
-            _ => TransactionType::SuspiciousActivity("Large cash deposit".to_string()),
+            3 => TransactionType::SuspiciousActivity("Large cash deposit".to_string()),
+            4 => TransactionType::Dispute(Self::pick_tx_id(rng, known_tx_ids)),
+            5 => TransactionType::Resolve(Self::pick_tx_id(rng, known_tx_ids)),
+            _ => TransactionType::Chargeback(Self::pick_tx_id(rng, known_tx_ids)),
         };
 
         let is_fraudulent = rng.gen_range(0..100) < 15;
 
+        // A customer showing up to resolve or charge back a dispute may
+        // already have funds on hold; one showing up to file a fresh
+        // dispute might be doing so from an account that was already
+        // locked by a prior chargeback.
+        let held_funds = if matches!(
+            transaction,
+            TransactionType::Resolve(_) | TransactionType::Chargeback(_)
+        ) {
+            rng.gen_range(50.0..2000.0)
+        } else {
+            0.0
+        };
+        let locked = matches!(transaction, TransactionType::Dispute(_)) && rng.gen_range(0..100) < 30;
+
         Customer {
             name,
             account_balance,
@@ -55,6 +87,19 @@ impl Customer {
             transaction,
             is_fraudulent,
             patience: 100.0,
+            held_funds,
+            locked,
+        }
+    }
+
+    /// Picks a `tx_id` for a Dispute/Resolve/Chargeback: usually a real id
+    /// from the ledger, occasionally a bogus one to model an invalid
+    /// reference that must be denied.
+    fn pick_tx_id(rng: &mut rand::rngs::ThreadRng, known_tx_ids: &[u32]) -> u32 {
+        if !known_tx_ids.is_empty() && rng.gen_bool(0.7) {
+            known_tx_ids[rng.gen_range(0..known_tx_ids.len())]
+        } else {
+            rng.gen_range(0..10_000)
         }
     }
 }
@@ -70,6 +115,10 @@ struct GameState {
     total_customers_served: u32,
     correct_fraud_detections: u32,
     rng: rand::rngs::ThreadRng,
+    /// Every processed transaction, keyed by its assigned `tx_id`, so
+    /// Dispute/Resolve/Chargeback transactions can look up what they refer to.
+    ledger: HashMap<u32, TransactionType>,
+    next_tx_id: u32,
 }
 
 impl GameState {
@@ -85,6 +134,8 @@ impl GameState {
             total_customers_served: 0,
             correct_fraud_detections: 0,
             rng: rand::thread_rng(),
+            ledger: HashMap::new(),
+            next_tx_id: 0,
         };
 
         state.spawn_customers(3);
@@ -93,12 +144,78 @@ impl GameState {
     }
 
     fn spawn_customers(&mut self, count: usize) {
+        let known_tx_ids: Vec<u32> = self.ledger.keys().copied().collect();
         for _ in 0..count {
-            let customer = Customer::new_random(&mut self.rng, self.day);
+            let customer = Customer::new_random(&mut self.rng, self.day, &known_tx_ids);
             self.customer_queue.push_back(customer);
         }
     }
 
+    /// The dollar amount a processed transaction represents, or 0.0 for
+    /// transaction types with no inherent amount of their own.
+    fn transaction_amount(tt: &TransactionType) -> f32 {
+        match tt {
+            TransactionType::Deposit(amount)
+            | TransactionType::Withdrawal(amount)
+            | TransactionType::LoanRequest(amount) => *amount,
+            _ => 0.0,
+        }
+    }
+
+    /// Records an approved transaction in the ledger under a freshly
+    /// assigned `tx_id` and returns that id.
+    fn record_transaction(&mut self, transaction: TransactionType) -> u32 {
+        let tx_id = self.next_tx_id;
+        self.next_tx_id += 1;
+        self.ledger.insert(tx_id, transaction);
+        tx_id
+    }
+
+    fn process_dispute(&mut self, tx_id: u32) {
+        let amount = self.ledger.get(&tx_id).map(Self::transaction_amount);
+        if let Some(customer) = &mut self.current_customer {
+            if customer.locked {
+                // New fraud category: disputing again from an account
+                // that's already been charged back.
+                self.score -= 100;
+            } else if let Some(amount) = amount {
+                customer.held_funds += amount;
+                self.score += 10;
+            } else {
+                self.score -= 20;
+            }
+        }
+    }
+
+    fn process_resolve(&mut self, tx_id: u32) {
+        let exists = self.ledger.contains_key(&tx_id);
+        if let Some(customer) = &mut self.current_customer {
+            if !exists {
+                self.score -= 20;
+            } else if customer.held_funds > 0.0 {
+                customer.account_balance += customer.held_funds;
+                customer.held_funds = 0.0;
+                self.score += 10;
+            } else {
+                self.score -= 10;
+            }
+        }
+    }
+
+    fn process_chargeback(&mut self, tx_id: u32) {
+        let exists = self.ledger.contains_key(&tx_id);
+        if let Some(customer) = &mut self.current_customer {
+            if !exists {
+                self.score -= 20;
+            } else {
+                self.bank_funds -= customer.held_funds;
+                customer.held_funds = 0.0;
+                customer.locked = true;
+                self.score += 15;
+            }
+        }
+    }
+
     fn next_customer(&mut self) {
         self.current_customer = self.customer_queue.pop_front();
 
@@ -109,17 +226,28 @@ impl GameState {
 
     fn approve_transaction(&mut self) {
         if let Some(customer) = &self.current_customer {
-            match &customer.transaction {
+            if customer.locked {
+                // Locked (already charged-back) accounts auto-deny.
+                self.score -= 20;
+                self.total_customers_served += 1;
+                self.next_customer();
+                return;
+            }
+
+            let transaction = customer.transaction.clone();
+            match &transaction {
                 TransactionType::Deposit(amount) => {
                     self.bank_funds += amount;
                     self.score += 10;
                     self.gain_experience(5);
+                    self.record_transaction(transaction.clone());
                 }
                 TransactionType::Withdrawal(amount) => {
                     if customer.account_balance >= *amount {
                         self.bank_funds -= amount;
                         self.score += 10;
                         self.gain_experience(5);
+                        self.record_transaction(transaction.clone());
                     } else {
                         self.score -= 20;
                     }
@@ -129,6 +257,7 @@ impl GameState {
                         self.bank_funds -= amount;
                         self.score += 50;
                         self.gain_experience(25);
+                        self.record_transaction(transaction.clone());
                     } else {
                         self.score -= 30;
                     }
@@ -139,8 +268,12 @@ impl GameState {
                     } else {
                         self.score += 20;
                         self.gain_experience(10);
+                        self.record_transaction(transaction.clone());
                     }
                 }
+                TransactionType::Dispute(tx_id) => self.process_dispute(*tx_id),
+                TransactionType::Resolve(tx_id) => self.process_resolve(*tx_id),
+                TransactionType::Chargeback(tx_id) => self.process_chargeback(*tx_id),
             }
 
             self.total_customers_served += 1;
@@ -160,6 +293,13 @@ impl GameState {
                         self.score -= 50;
                     }
                 }
+                TransactionType::Dispute(_) if customer.locked => {
+                    // Correctly caught a dispute filed from an account
+                    // that's already been charged back.
+                    self.score += 200;
+                    self.correct_fraud_detections += 1;
+                    self.gain_experience(50);
+                }
                 _ => {
                     self.score -= 10;
                 }
@@ -239,6 +379,7 @@ impl EventHandler for GameState {
                 format!("CUSTOMER: {}", customer.name),
                 format!("Account Balance: ${:.2}", customer.account_balance),
                 format!("Credit Score: {}", customer.credit_score),
+                format!("Held Funds: ${:.2}{}", customer.held_funds, if customer.locked { " (LOCKED)" } else { "" }),
                 format!(""),
                 format!("REQUEST:"),
                 match &customer.transaction {
@@ -246,6 +387,9 @@ impl EventHandler for GameState {
                     TransactionType::Withdrawal(amt) => format!("Withdraw ${:.2}", amt),
                     TransactionType::LoanRequest(amt) => format!("Loan Application: ${:.2}", amt),
                     TransactionType::SuspiciousActivity(desc) => format!("⚠️ ALERT: {}", desc),
+                    TransactionType::Dispute(tx_id) => format!("Dispute transaction #{}", tx_id),
+                    TransactionType::Resolve(tx_id) => format!("Resolve dispute on #{}", tx_id),
+                    TransactionType::Chargeback(tx_id) => format!("Chargeback transaction #{}", tx_id),
                 },
                 format!(""),
                 format!("Patience: {:.0}%", customer.patience),
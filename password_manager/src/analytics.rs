@@ -1,10 +1,11 @@
-#[allow(unused_imports)]
-use crate::models::{Credential, Vault};
-use crate::security::{analyze_password, is_common_password, is_weak_password, PasswordStrength};
+use crate::models::Vault;
+use crate::security::{
+    analyze_password, check_password_breach_with, is_common_password, is_weak_password,
+    BreachSource, PasswordStrength,
+};
 use std::collections::HashMap;
 
 /// Vault health score (0-100)
-#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct VaultHealth {
     pub overall_score: u32,
@@ -15,12 +16,14 @@ pub struct VaultHealth {
     pub strong_passwords: usize,
     pub common_passwords: usize,
     pub with_totp: usize,
+    /// Only populated when `analyze_vault_health_with_breach_check` ran the
+    /// breach pass; stays `0` for the offline `analyze_vault_health`.
+    pub breached_passwords: usize,
     pub average_password_age_days: f64,
     pub recommendations: Vec<String>,
 }
 
 impl VaultHealth {
-    #[allow(dead_code)]
     pub fn score_category(&self) -> &'static str {
         match self.overall_score {
             0..=20 => "Critical",
@@ -32,7 +35,6 @@ impl VaultHealth {
         }
     }
 
-    #[allow(dead_code)]
     pub fn score_color(&self) -> &'static str {
         match self.overall_score {
             0..=20 => "red",
@@ -46,8 +48,27 @@ impl VaultHealth {
 }
 
 /// Analyze vault health
-#[allow(dead_code)]
 pub fn analyze_vault_health(vault: &Vault, old_password_threshold_days: i64) -> VaultHealth {
+    analyze_vault_health_impl(vault, old_password_threshold_days, None)
+}
+
+/// Same as `analyze_vault_health`, but additionally runs each credential's
+/// password through `breach_source` (HIBP's k-anonymity range endpoint by
+/// default — see `BreachSource`) and factors the breached fraction into
+/// both the score and `breached_passwords`.
+pub fn analyze_vault_health_with_breach_check(
+    vault: &Vault,
+    old_password_threshold_days: i64,
+    breach_source: &dyn BreachSource,
+) -> VaultHealth {
+    analyze_vault_health_impl(vault, old_password_threshold_days, Some(breach_source))
+}
+
+fn analyze_vault_health_impl(
+    vault: &Vault,
+    old_password_threshold_days: i64,
+    breach_source: Option<&dyn BreachSource>,
+) -> VaultHealth {
     let total_credentials = vault.credentials.len();
 
     if total_credentials == 0 {
@@ -60,6 +81,7 @@ pub fn analyze_vault_health(vault: &Vault, old_password_threshold_days: i64) ->
             strong_passwords: 0,
             common_passwords: 0,
             with_totp: 0,
+            breached_passwords: 0,
             average_password_age_days: 0.0,
             recommendations: vec![
                 "Add credentials to start using the password manager.".to_string()
@@ -72,6 +94,7 @@ pub fn analyze_vault_health(vault: &Vault, old_password_threshold_days: i64) ->
     let mut strong_passwords = 0;
     let mut common_passwords = 0;
     let mut with_totp = 0;
+    let mut breached_passwords = 0;
     let mut total_age_days = 0i64;
 
     for cred in &vault.credentials {
@@ -95,6 +118,15 @@ pub fn analyze_vault_health(vault: &Vault, old_password_threshold_days: i64) ->
             with_totp += 1;
         }
 
+        // Check breach status, if a source was provided. A failed lookup is
+        // treated as "not breached" rather than aborting the whole report.
+        if let Some(source) = breach_source {
+            let breach_count = check_password_breach_with(source, &cred.password).unwrap_or(0);
+            if breach_count > 0 {
+                breached_passwords += 1;
+            }
+        }
+
         // Add to age
         total_age_days += cred.password_age_days();
     }
@@ -121,11 +153,13 @@ pub fn analyze_vault_health(vault: &Vault, old_password_threshold_days: i64) ->
     let reused_penalty = (reused_passwords as f64 / total_credentials as f64 * 25.0) as u32;
     let old_penalty = (old_passwords as f64 / total_credentials as f64 * 20.0) as u32;
     let common_penalty = (common_passwords as f64 / total_credentials as f64 * 15.0) as u32;
+    let breach_penalty = (breached_passwords as f64 / total_credentials as f64 * 25.0) as u32;
 
     score = score.saturating_sub(weak_penalty);
     score = score.saturating_sub(reused_penalty);
     score = score.saturating_sub(old_penalty);
     score = score.saturating_sub(common_penalty);
+    score = score.saturating_sub(breach_penalty);
 
     // Bonus for TOTP
     let totp_bonus = (with_totp as f64 / total_credentials as f64 * 10.0) as u32;
@@ -162,6 +196,13 @@ pub fn analyze_vault_health(vault: &Vault, old_password_threshold_days: i64) ->
         ));
     }
 
+    if breached_passwords > 0 {
+        recommendations.push(format!(
+            "Change {} password(s) found in known data breaches!",
+            breached_passwords
+        ));
+    }
+
     if with_totp < total_credentials / 2 {
         recommendations.push("Enable 2FA/TOTP for more accounts to improve security.".to_string());
     }
@@ -179,13 +220,13 @@ pub fn analyze_vault_health(vault: &Vault, old_password_threshold_days: i64) ->
         strong_passwords,
         common_passwords,
         with_totp,
+        breached_passwords,
         average_password_age_days,
         recommendations,
     }
 }
 
 /// Detailed password report for a single credential
-#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct PasswordReport {
     pub service: String,
@@ -196,12 +237,33 @@ pub struct PasswordReport {
     pub is_old: bool,
     pub age_days: i64,
     pub has_totp: bool,
+    /// Only populated by `generate_password_reports_with_breach_check`;
+    /// stays `None` for the offline `generate_password_reports`.
+    pub breach_count: Option<u64>,
     pub warnings: Vec<String>,
 }
 
 /// Generate detailed reports for all credentials
-#[allow(dead_code)]
 pub fn generate_password_reports(vault: &Vault, old_threshold_days: i64) -> Vec<PasswordReport> {
+    generate_password_reports_impl(vault, old_threshold_days, None)
+}
+
+/// Same as `generate_password_reports`, but additionally looks up each
+/// credential's password against `breach_source` and populates
+/// `breach_count`.
+pub fn generate_password_reports_with_breach_check(
+    vault: &Vault,
+    old_threshold_days: i64,
+    breach_source: &dyn BreachSource,
+) -> Vec<PasswordReport> {
+    generate_password_reports_impl(vault, old_threshold_days, Some(breach_source))
+}
+
+fn generate_password_reports_impl(
+    vault: &Vault,
+    old_threshold_days: i64,
+    breach_source: Option<&dyn BreachSource>,
+) -> Vec<PasswordReport> {
     let reused_map = vault.find_reused_passwords();
 
     let mut reused_passwords: HashMap<String, bool> = HashMap::new();
@@ -221,6 +283,8 @@ pub fn generate_password_reports(vault: &Vault, old_threshold_days: i64) -> Vec<
             let is_old = cred.is_old(old_threshold_days);
             let age_days = cred.password_age_days();
             let has_totp = cred.totp_secret.is_some();
+            let breach_count = breach_source
+                .map(|source| check_password_breach_with(source, &cred.password).unwrap_or(0));
 
             let analysis = analyze_password(&cred.password, &[&cred.service, &cred.username]);
             let strength = analysis.strength;
@@ -238,6 +302,12 @@ pub fn generate_password_reports(vault: &Vault, old_threshold_days: i64) -> Vec<
             if is_old {
                 warnings.push(format!("Password is {} days old", age_days));
             }
+            if matches!(breach_count, Some(count) if count > 0) {
+                warnings.push(format!(
+                    "Password found in {} known breach(es)",
+                    breach_count.unwrap()
+                ));
+            }
             if !has_totp {
                 warnings.push("Consider enabling 2FA/TOTP".to_string());
             }
@@ -251,6 +321,7 @@ pub fn generate_password_reports(vault: &Vault, old_threshold_days: i64) -> Vec<
                 is_old,
                 age_days,
                 has_totp,
+                breach_count,
                 warnings,
             }
         })
@@ -258,7 +329,6 @@ pub fn generate_password_reports(vault: &Vault, old_threshold_days: i64) -> Vec<
 }
 
 /// Find credentials that need attention
-#[allow(dead_code)]
 pub fn find_credentials_needing_attention(vault: &Vault, old_threshold_days: i64) -> Vec<String> {
     let reports = generate_password_reports(vault, old_threshold_days);
 
@@ -310,6 +380,7 @@ mod tests {
             strong_passwords: 10,
             common_passwords: 0,
             with_totp: 5,
+            breached_passwords: 0,
             average_password_age_days: 30.0,
             recommendations: vec![],
         };
@@ -333,4 +404,96 @@ mod tests {
         assert_eq!(reports.len(), 1);
         assert!(reports[0].is_weak || reports[0].warnings.len() > 0);
     }
+
+    /// Canned `BreachSource` that returns a fixed range response for every
+    /// prefix, so breach-check tests never touch the network.
+    struct MockBreachSource {
+        body: &'static str,
+    }
+
+    impl crate::security::BreachSource for MockBreachSource {
+        fn range_for_prefix(&self, _prefix: &str) -> crate::errors::Result<String> {
+            Ok(self.body.to_string())
+        }
+    }
+
+    #[test]
+    fn test_vault_health_with_breach_check_flags_breached_password() {
+        let mut vault = Vault::new();
+        vault
+            .add_credential(Credential::new(
+                "breached.example".to_string(),
+                "user".to_string(),
+                "hunter2".to_string(),
+                None,
+            ))
+            .unwrap();
+
+        // SHA-1("hunter2") = F3BBB|D66A63D4BF1747940578EC3D0103530E21D
+        let source = MockBreachSource {
+            body: "D66A63D4BF1747940578EC3D0103530E21D:42\r\nAAAA:1",
+        };
+
+        let health = analyze_vault_health_with_breach_check(&vault, 90, &source);
+        assert_eq!(health.breached_passwords, 1);
+        assert!(health.overall_score < 100);
+        assert!(health
+            .recommendations
+            .iter()
+            .any(|r| r.contains("known data breaches")));
+    }
+
+    #[test]
+    fn test_vault_health_with_breach_check_ignores_non_matching_suffix() {
+        let mut vault = Vault::new();
+        vault
+            .add_credential(Credential::new(
+                "clean.example".to_string(),
+                "user".to_string(),
+                "Tr0ub4dor&3-Zebra".to_string(),
+                None,
+            ))
+            .unwrap();
+
+        let source = MockBreachSource { body: "AAAA:1" };
+        let health = analyze_vault_health_with_breach_check(&vault, 90, &source);
+        assert_eq!(health.breached_passwords, 0);
+    }
+
+    #[test]
+    fn test_password_reports_with_breach_check_sets_breach_count() {
+        let mut vault = Vault::new();
+        vault
+            .add_credential(Credential::new(
+                "breached.example".to_string(),
+                "user".to_string(),
+                "hunter2".to_string(),
+                None,
+            ))
+            .unwrap();
+
+        let source = MockBreachSource {
+            body: "D66A63D4BF1747940578EC3D0103530E21D:42",
+        };
+
+        let reports = generate_password_reports_with_breach_check(&vault, 90, &source);
+        assert_eq!(reports[0].breach_count, Some(42));
+        assert!(reports[0].warnings.iter().any(|w| w.contains("breach")));
+    }
+
+    #[test]
+    fn test_password_reports_without_breach_check_leaves_breach_count_none() {
+        let mut vault = Vault::new();
+        vault
+            .add_credential(Credential::new(
+                "test".to_string(),
+                "user".to_string(),
+                "password123".to_string(),
+                None,
+            ))
+            .unwrap();
+
+        let reports = generate_password_reports(&vault, 90);
+        assert_eq!(reports[0].breach_count, None);
+    }
 }
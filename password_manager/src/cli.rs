@@ -1,12 +1,22 @@
 use clap::{Parser, Subcommand};
 use colored::*;
+use dialoguer::FuzzySelect;
+use regex::Regex;
 use rpassword::read_password;
 use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
+use crate::analytics;
 use crate::crypto::generate_password;
 use crate::errors::{PasswordManagerError, Result};
+use crate::format::Format;
 use crate::models::Credential;
-use crate::vault::VaultManager;
+use crate::security::{
+    batch_check_passwords, check_password_breach_online, format_totp_code, generate_totp,
+    HibpBreachSource, OtpMode, TotpConfig,
+};
+use crate::storage::{FileStorage, S3Storage, VaultStorage};
+use crate::vault::{Locked, Unlocked, VaultManager};
 
 #[derive(Parser)]
 #[command(name = "password_manager")]
@@ -14,10 +24,39 @@ use crate::vault::VaultManager;
 #[command(version = "1.0")]
 #[command(about = "A secure CLI password manager", long_about = None)]
 pub struct Cli {
+    /// Master password, for non-interactive/scripted use (CI, pipelines).
+    /// Pass "-" to read one line from stdin instead of putting the secret in
+    /// argv, where it would be visible in shell history and `ps`. Omit (or
+    /// pass an empty value) to be prompted interactively, as before.
+    #[arg(long, global = true)]
+    pub master_password: Option<String>,
+
+    /// Which backend stores the encrypted vault blob
+    #[arg(long, global = true, value_enum, default_value = "file", requires_if("s3", "s3_url"))]
+    pub storage: StorageBackend,
+
+    /// Object URL for `--storage s3` (e.g. a pre-signed PUT/GET URL, or a
+    /// bucket endpoint behind an authenticating reverse proxy)
+    #[arg(long, global = true)]
+    pub s3_url: Option<String>,
+
+    /// Bearer token for `--storage s3`, if the endpoint requires one
+    #[arg(long, global = true)]
+    pub s3_token: Option<String>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
+/// Which `VaultStorage` backend to use (see `crate::storage`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum StorageBackend {
+    /// Local file on disk (default)
+    File,
+    /// S3-compatible object store, addressed via `--s3-url`
+    S3,
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
     /// Initialize a new password vault
@@ -40,6 +79,17 @@ pub enum Commands {
         /// Password length for generation (default: 24)
         #[arg(short = 'l', long, default_value = "24")]
         length: usize,
+
+        /// Password for the new credential, for scripted use. Pass "-" to
+        /// read one line from stdin. Ignored if `--generate` is set. Omit
+        /// (or pass an empty value) to be prompted interactively.
+        #[arg(short, long)]
+        password: Option<String>,
+
+        /// Base32 TOTP secret, for vaulting the service's 2FA alongside its
+        /// password. See the `totp` command to read back codes.
+        #[arg(long)]
+        totp_secret: Option<String>,
     },
 
     /// Get a credential (copies password to clipboard)
@@ -52,8 +102,18 @@ pub enum Commands {
         show: bool,
     },
 
-    /// List all stored credentials
-    List,
+    /// List all stored credentials, via an interactive type-to-filter picker
+    List {
+        /// Only show credentials whose service or username match this text
+        /// before the picker opens
+        #[arg(short, long)]
+        filter: Option<String>,
+
+        /// Treat `--filter` as a regular expression instead of a plain,
+        /// case-insensitive substring match
+        #[arg(long, requires = "filter")]
+        regex: bool,
+    },
 
     /// Search credentials by service or username
     Search {
@@ -73,6 +133,16 @@ pub enum Commands {
         /// Password length for generation (default: 24)
         #[arg(short = 'l', long, default_value = "24")]
         length: usize,
+
+        /// New password, for scripted use. Pass "-" to read one line from
+        /// stdin. Ignored if `--generate` is set. Omit (or pass an empty
+        /// value) to be prompted interactively.
+        #[arg(short, long)]
+        password: Option<String>,
+
+        /// Replace the stored base32 TOTP secret. Omit to leave it unchanged.
+        #[arg(long)]
+        totp_secret: Option<String>,
     },
 
     /// Remove a credential
@@ -103,6 +173,100 @@ pub enum Commands {
         #[arg(long)]
         no_symbols: bool,
     },
+
+    /// Export all credentials to a plaintext file
+    Export {
+        /// Output file path
+        path: PathBuf,
+
+        /// Export format
+        #[arg(short, long, value_enum, default_value = "native")]
+        format: Format,
+    },
+
+    /// Import credentials from a plaintext file
+    Import {
+        /// Input file path
+        path: PathBuf,
+
+        /// Import format
+        #[arg(short, long, value_enum, default_value = "native")]
+        format: Format,
+    },
+
+    /// Rotate the master password and re-encrypt the vault under it
+    ChangeMasterPassword,
+
+    /// Print the current TOTP code for a credential's stored secret
+    Totp {
+        /// Service name
+        service: String,
+    },
+
+    /// Check whether a credential's password has appeared in a known data
+    /// breach, via the Have I Been Pwned range API. Omit `service` to check
+    /// every stored credential.
+    Check {
+        /// Service name. Omit to check every stored credential.
+        service: Option<String>,
+    },
+
+    /// Report a security overview of the vault: weak, reused, and old
+    /// passwords
+    Audit {
+        /// Age in days before a password is flagged as old
+        #[arg(long, default_value = "90")]
+        max_age_days: i64,
+    },
+
+    /// Show the tamper-evident audit log of every operation performed on
+    /// the vault, and verify its hash chain is unbroken
+    AuditLog,
+
+    /// Freeze an immutable snapshot of the vault's current credentials and
+    /// settings, or list previously frozen generations
+    Snapshot {
+        /// List retained snapshot generations instead of freezing a new one
+        #[arg(short, long)]
+        list: bool,
+    },
+
+    /// Restore the vault's credentials and settings from a previously
+    /// frozen snapshot generation (see `snapshot --list`)
+    SnapshotRollback {
+        /// Generation number to restore
+        generation: u64,
+    },
+
+    /// Undo the most recent save by restoring the storage backend's
+    /// rolling backup (e.g. `vault.enc.bak` for the file backend). Fails if
+    /// the backend keeps no backup, or none exists yet.
+    Rollback,
+
+    /// Re-run Argon2id calibration for this machine and re-encrypt the
+    /// vault under the new parameters
+    Recalibrate,
+}
+
+/// Builds the `VaultStorage` backend selected by `--storage` (and, for
+/// `StorageBackend::S3`, `--s3-url`/`--s3-token`). `StorageBackend::File`
+/// uses `VaultManager::default_vault_path`, same as before this flag
+/// existed.
+pub fn resolve_storage(cli: &Cli) -> Result<Box<dyn VaultStorage>> {
+    match cli.storage {
+        StorageBackend::File => {
+            let vault_path = VaultManager::default_vault_path()?;
+            Ok(Box::new(FileStorage::new(vault_path)))
+        }
+        StorageBackend::S3 => {
+            let object_url = cli.s3_url.clone().ok_or_else(|| {
+                PasswordManagerError::InvalidInput(
+                    "--storage s3 requires --s3-url".to_string(),
+                )
+            })?;
+            Ok(Box::new(S3Storage::new(object_url, cli.s3_token.clone())))
+        }
+    }
 }
 
 /// Prompt for master password
@@ -134,8 +298,80 @@ pub fn prompt_input(prompt: &str) -> Result<String> {
     Ok(input.trim().to_string())
 }
 
+/// Resolves a secret from a `--password`/`--master-password`-style flag for
+/// non-interactive/scripted use: `None` or `Some("")` falls back to
+/// `prompt_master_password` as before; `Some("-")` reads one line from
+/// stdin instead (so the secret never appears in argv or shell history);
+/// any other value is used as-is, skipping the prompt entirely.
+fn resolve_secret(flag: Option<&str>, prompt: &str) -> Result<String> {
+    match flag {
+        None | Some("") => prompt_master_password(prompt),
+        Some("-") => {
+            let mut line = String::new();
+            io::stdin().read_line(&mut line)?;
+            let line = line.trim_end_matches(['\n', '\r']).to_string();
+            if line.is_empty() {
+                return Err(PasswordManagerError::InvalidInput(
+                    "Password cannot be empty".to_string(),
+                ));
+            }
+            Ok(line)
+        }
+        Some(value) => Ok(value.to_string()),
+    }
+}
+
+/// Builds a `Credential` predicate for `--filter`: a plain, case-insensitive
+/// substring match over `service`/`username` by default, or a regular
+/// expression over the same pair when `use_regex` is set.
+fn build_filter(filter: Option<&str>, use_regex: bool) -> Result<Box<dyn Fn(&Credential) -> bool>> {
+    match filter {
+        None => Ok(Box::new(|_: &Credential| true)),
+        Some(pattern) if use_regex => {
+            let re = Regex::new(pattern)
+                .map_err(|e| PasswordManagerError::InvalidInput(format!("Invalid filter regex: {}", e)))?;
+            Ok(Box::new(move |c: &Credential| re.is_match(&format!("{} {}", c.service, c.username))))
+        }
+        Some(pattern) => {
+            let needle = pattern.to_lowercase();
+            Ok(Box::new(move |c: &Credential| {
+                c.service.to_lowercase().contains(&needle) || c.username.to_lowercase().contains(&needle)
+            }))
+        }
+    }
+}
+
+/// Renders a scrollable, incremental type-to-filter picker over
+/// `candidates` and returns the chosen credential. `initial_text` pre-fills
+/// the picker's query (used by `handle_get`'s fallback so the service name
+/// that didn't match exactly carries over as a starting filter).
+fn pick_credential<'a>(candidates: &[&'a Credential], initial_text: &str) -> Result<&'a Credential> {
+    if candidates.is_empty() {
+        return Err(PasswordManagerError::CredentialNotFound(
+            "no credentials match".to_string(),
+        ));
+    }
+
+    let items: Vec<String> = candidates
+        .iter()
+        .map(|c| format!("{} ({})", c.service, c.username))
+        .collect();
+
+    let selection = FuzzySelect::new()
+        .with_prompt("Select a credential")
+        .with_initial_text(initial_text)
+        .items(&items)
+        .default(0)
+        .interact_opt()
+        .map_err(|e| PasswordManagerError::InvalidInput(format!("Selection failed: {}", e)))?;
+
+    selection
+        .map(|index| candidates[index])
+        .ok_or_else(|| PasswordManagerError::InvalidInput("Selection cancelled".to_string()))
+}
+
 /// Handle the init command
-pub fn handle_init(vault_manager: &mut VaultManager) -> Result<()> {
+pub fn handle_init(vault_manager: VaultManager<Locked>, master_password: Option<&str>) -> Result<()> {
     if vault_manager.vault_exists() {
         println!(
             "{}",
@@ -147,14 +383,22 @@ pub fn handle_init(vault_manager: &mut VaultManager) -> Result<()> {
     println!("{}", "Initializing new password vault...".bright_green());
     println!();
 
-    let password = prompt_master_password("Enter master password: ")?;
-    let confirm = prompt_master_password("Confirm master password: ")?;
-
-    if password != confirm {
-        return Err(PasswordManagerError::InvalidInput(
-            "Passwords do not match".to_string(),
-        ));
-    }
+    // A master password supplied non-interactively is only entered once —
+    // the confirmation prompt exists to catch a typo at an interactive
+    // terminal, which doesn't apply when the value came from a flag or stdin.
+    let password = match master_password {
+        Some(value) if !value.is_empty() => resolve_secret(Some(value), "Enter master password: ")?,
+        _ => {
+            let password = prompt_master_password("Enter master password: ")?;
+            let confirm = prompt_master_password("Confirm master password: ")?;
+            if password != confirm {
+                return Err(PasswordManagerError::InvalidInput(
+                    "Passwords do not match".to_string(),
+                ));
+            }
+            password
+        }
+    };
 
     vault_manager.initialize(&password)?;
 
@@ -167,13 +411,16 @@ pub fn handle_init(vault_manager: &mut VaultManager) -> Result<()> {
 
 /// Handle the add command
 pub fn handle_add(
-    vault_manager: &mut VaultManager,
+    vault_manager: VaultManager<Locked>,
     service: Option<String>,
     username: Option<String>,
     generate: bool,
     length: usize,
+    password: Option<String>,
+    totp_secret: Option<String>,
+    master_password: Option<&str>,
 ) -> Result<()> {
-    unlock_vault(vault_manager)?;
+    let mut vault_manager = unlock_vault(vault_manager, master_password)?;
 
     // Prompt for service if not provided
     let service = match service {
@@ -203,7 +450,7 @@ pub fn handle_add(
         );
         pwd
     } else {
-        prompt_master_password("Password: ")?
+        resolve_secret(password.as_deref(), "Password: ")?
     };
 
     // Optional notes
@@ -214,7 +461,8 @@ pub fn handle_add(
         Some(notes_input)
     };
 
-    let credential = Credential::new(service.clone(), username, password, notes);
+    let mut credential = Credential::new(service.clone(), username, password, notes);
+    credential.totp_secret = totp_secret;
     vault_manager.add_credential(credential)?;
 
     println!();
@@ -228,8 +476,22 @@ pub fn handle_add(
 }
 
 /// Handle the get command
-pub fn handle_get(vault_manager: &VaultManager, service: &str, show: bool) -> Result<()> {
-    let credential = vault_manager.get_credential(service)?;
+pub fn handle_get(
+    vault_manager: VaultManager<Locked>,
+    service: &str,
+    show: bool,
+    master_password: Option<&str>,
+) -> Result<()> {
+    let vault_manager = unlock_vault(vault_manager, master_password)?;
+
+    let credential = match vault_manager.get_credential(service) {
+        Ok(credential) => credential,
+        Err(PasswordManagerError::CredentialNotFound(_)) => {
+            let candidates: Vec<&Credential> = vault_manager.list_all().iter().collect();
+            pick_credential(&candidates, service)?
+        }
+        Err(e) => return Err(e),
+    };
 
     if show {
         println!();
@@ -269,37 +531,62 @@ pub fn handle_get(vault_manager: &VaultManager, service: &str, show: bool) -> Re
     Ok(())
 }
 
-/// Handle the list command
-pub fn handle_list(vault_manager: &VaultManager) -> Result<()> {
-    let credentials = vault_manager.list_all()?;
+/// Handle the list command: narrows the vault down by `--filter`/`--regex`,
+/// then hands the result to `pick_credential` for interactive selection and
+/// copies the chosen credential's password to the clipboard.
+pub fn handle_list(
+    vault_manager: VaultManager<Locked>,
+    filter: Option<String>,
+    use_regex: bool,
+    master_password: Option<&str>,
+) -> Result<()> {
+    let vault_manager = unlock_vault(vault_manager, master_password)?;
+    let credentials = vault_manager.list_all();
 
     if credentials.is_empty() {
         println!("{}", "No credentials stored yet.".yellow());
         return Ok(());
     }
 
-    println!();
-    println!("{}", "Stored Credentials:".bright_cyan().bold());
-    println!("{}", "─".repeat(80).bright_black());
+    let matches = build_filter(filter.as_deref(), use_regex)?;
+    let candidates: Vec<&Credential> = credentials.iter().filter(|c| matches(c)).collect();
 
-    for cred in credentials {
+    if candidates.is_empty() {
         println!(
-            "  {} {} ({})",
-            "•".bright_green(),
-            cred.service.bright_white().bold(),
-            cred.username.bright_black()
+            "{} '{}'",
+            "No credentials match".yellow(),
+            filter.unwrap_or_default()
         );
+        return Ok(());
     }
 
-    println!("{}", "─".repeat(80).bright_black());
-    println!("{} credentials found", credentials.len());
+    let selected = pick_credential(&candidates, "")?;
+
+    match cli_clipboard::set_contents(selected.password.clone()) {
+        Ok(_) => {
+            println!(
+                "{} {} {}",
+                "✓ Password for".bright_green(),
+                selected.service.bright_white(),
+                "copied to clipboard".bright_green()
+            );
+        }
+        Err(e) => {
+            return Err(PasswordManagerError::ClipboardError(e.to_string()));
+        }
+    }
 
     Ok(())
 }
 
 /// Handle the search command
-pub fn handle_search(vault_manager: &VaultManager, query: &str) -> Result<()> {
-    let results = vault_manager.search(query)?;
+pub fn handle_search(
+    vault_manager: VaultManager<Locked>,
+    query: &str,
+    master_password: Option<&str>,
+) -> Result<()> {
+    let vault_manager = unlock_vault(vault_manager, master_password)?;
+    let results = vault_manager.search(query);
 
     if results.is_empty() {
         println!("{} '{}'", "No credentials found matching".yellow(), query);
@@ -330,11 +617,16 @@ pub fn handle_search(vault_manager: &VaultManager, query: &str) -> Result<()> {
 
 /// Handle the update command
 pub fn handle_update(
-    vault_manager: &mut VaultManager,
+    vault_manager: VaultManager<Locked>,
     service: &str,
     generate: bool,
     length: usize,
+    password: Option<String>,
+    totp_secret: Option<String>,
+    master_password: Option<&str>,
 ) -> Result<()> {
+    let mut vault_manager = unlock_vault(vault_manager, master_password)?;
+
     // Verify credential exists
     let _ = vault_manager.get_credential(service)?;
 
@@ -347,11 +639,15 @@ pub fn handle_update(
         );
         pwd
     } else {
-        prompt_master_password("New password: ")?
+        resolve_secret(password.as_deref(), "New password: ")?
     };
 
     vault_manager.update_credential(service, new_password)?;
 
+    if let Some(totp_secret) = totp_secret {
+        vault_manager.set_totp_secret(service, Some(totp_secret))?;
+    }
+
     println!();
     println!(
         "{} {}",
@@ -363,7 +659,13 @@ pub fn handle_update(
 }
 
 /// Handle the remove command
-pub fn handle_remove(vault_manager: &mut VaultManager, service: &str) -> Result<()> {
+pub fn handle_remove(
+    vault_manager: VaultManager<Locked>,
+    service: &str,
+    master_password: Option<&str>,
+) -> Result<()> {
+    let mut vault_manager = unlock_vault(vault_manager, master_password)?;
+
     // Verify credential exists
     let _ = vault_manager.get_credential(service)?;
 
@@ -425,14 +727,429 @@ pub fn handle_generate(
     Ok(())
 }
 
-/// Unlock vault by prompting for master password
-fn unlock_vault(vault_manager: &mut VaultManager) -> Result<()> {
+/// Handle the export command
+pub fn handle_export(
+    vault_manager: VaultManager<Locked>,
+    path: &Path,
+    format: Format,
+    master_password: Option<&str>,
+) -> Result<()> {
+    let vault_manager = unlock_vault(vault_manager, master_password)?;
+    vault_manager.export(format, path)?;
+
+    println!();
+    println!(
+        "{} {}",
+        "✓ Vault exported to".bright_green(),
+        path.display().to_string().bright_white()
+    );
+
+    Ok(())
+}
+
+/// Handle the import command
+pub fn handle_import(
+    vault_manager: VaultManager<Locked>,
+    path: &Path,
+    format: Format,
+    master_password: Option<&str>,
+) -> Result<()> {
+    let mut vault_manager = unlock_vault(vault_manager, master_password)?;
+    let imported_count = vault_manager.import(format, path)?;
+
+    println!();
+    println!(
+        "{} {} {}",
+        "✓ Imported".bright_green(),
+        imported_count.to_string().bright_white(),
+        "credential(s)".bright_green()
+    );
+
+    Ok(())
+}
+
+/// Handle the change-master-password command
+pub fn handle_change_master_password(
+    vault_manager: VaultManager<Locked>,
+    master_password: Option<&str>,
+) -> Result<()> {
     if !vault_manager.vault_exists() {
         return Err(PasswordManagerError::VaultNotFound);
     }
 
-    let password = prompt_master_password("Master password: ")?;
-    vault_manager.unlock(&password)?;
+    let current_password = resolve_secret(master_password, "Current master password: ")?;
+    let mut vault_manager = vault_manager.unlock(&current_password)?;
+
+    let new_password = prompt_master_password("New master password: ")?;
+    let confirm = prompt_master_password("Confirm new master password: ")?;
+
+    if new_password != confirm {
+        return Err(PasswordManagerError::InvalidInput(
+            "Passwords do not match".to_string(),
+        ));
+    }
+
+    vault_manager.change_master_password(&current_password, &new_password)?;
+
+    println!();
+    println!("{}", "✓ Master password changed successfully!".bright_green());
+    println!(
+        "{}",
+        "The vault has been re-encrypted under the new password.".green()
+    );
 
     Ok(())
 }
+
+/// Handle the totp command: reads a credential's stored secret, generates
+/// the current code (RFC 6238), and copies it to the clipboard.
+pub fn handle_totp(
+    vault_manager: VaultManager<Locked>,
+    service: &str,
+    master_password: Option<&str>,
+) -> Result<()> {
+    let vault_manager = unlock_vault(vault_manager, master_password)?;
+    let credential = vault_manager.get_credential(service)?;
+
+    let secret = credential.totp_secret.as_deref().ok_or_else(|| {
+        PasswordManagerError::InvalidInput(format!("No TOTP secret stored for '{}'", service))
+    })?;
+
+    let code = generate_totp(secret).map_err(PasswordManagerError::InvalidInput)?;
+
+    let period = match TotpConfig::default().mode {
+        OtpMode::Totp { period } => period,
+        OtpMode::Hotp { .. } => 30,
+    };
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_err(|e| PasswordManagerError::InvalidInput(format!("Failed to get system time: {}", e)))?
+        .as_secs();
+    let seconds_remaining = period - (now % period);
+
+    println!();
+    println!(
+        "{}: {}",
+        "Code".bright_cyan(),
+        format_totp_code(&code).bright_white().bold()
+    );
+    println!(
+        "{}: {}s",
+        "Expires in".bright_cyan(),
+        seconds_remaining
+    );
+
+    match cli_clipboard::set_contents(code) {
+        Ok(_) => println!("{}", "✓ Code copied to clipboard".bright_green()),
+        Err(e) => println!("{} {}", "Warning: Could not copy to clipboard:".yellow(), e),
+    }
+
+    Ok(())
+}
+
+/// Handle the check command: looks up each targeted credential's password
+/// against the HIBP range API (k-anonymity — only a 5-char hash prefix is
+/// ever sent) and reports whether it's appeared in a known breach. Checks
+/// every stored credential when `service` is omitted.
+pub fn handle_check(
+    vault_manager: VaultManager<Locked>,
+    service: Option<String>,
+    master_password: Option<&str>,
+) -> Result<()> {
+    let vault_manager = unlock_vault(vault_manager, master_password)?;
+
+    println!();
+    match service {
+        Some(service) => {
+            let credential = vault_manager.get_credential(&service)?;
+            let (is_breached, count, _) = check_password_breach_online(&credential.password)?;
+            print_breach_result(&service, is_breached, count);
+        }
+        None => {
+            let credentials = vault_manager.list_all();
+            if credentials.is_empty() {
+                println!("{}", "No credentials stored yet.".yellow());
+                return Ok(());
+            }
+
+            let passwords: Vec<String> = credentials.iter().map(|c| c.password.clone()).collect();
+            let results = batch_check_passwords(&passwords);
+
+            for (credential, (_, is_breached, count, _)) in credentials.iter().zip(results) {
+                print_breach_result(&credential.service, is_breached, count);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints one credential's breach result: a red warning with the breach
+/// count when found, a green "not found" otherwise.
+fn print_breach_result(service: &str, is_breached: bool, count: u32) {
+    if is_breached {
+        println!(
+            "{} {} {}",
+            "✗".red(),
+            service.bright_white(),
+            format!("found in {} breach(es)!", count).red()
+        );
+    } else {
+        println!(
+            "{} {} {}",
+            "✓".green(),
+            service.bright_white(),
+            "not found in any known breach".green()
+        );
+    }
+}
+
+/// Handle the audit command: runs the vault through
+/// `analytics::analyze_vault_health_with_breach_check` (checking every
+/// password against the real HIBP API via `HibpBreachSource`) and prints the
+/// resulting health score plus a per-credential breakdown of what's weak,
+/// reused, old, common, or breached.
+pub fn handle_audit(
+    vault_manager: VaultManager<Locked>,
+    max_age_days: i64,
+    master_password: Option<&str>,
+) -> Result<()> {
+    let vault_manager = unlock_vault(vault_manager, master_password)?;
+
+    if vault_manager.list_all().is_empty() {
+        println!("{}", "No credentials stored yet.".yellow());
+        return Ok(());
+    }
+
+    let health = analytics::analyze_vault_health_with_breach_check(
+        vault_manager.vault(),
+        max_age_days,
+        &HibpBreachSource,
+    );
+    let reports = analytics::generate_password_reports_with_breach_check(
+        vault_manager.vault(),
+        max_age_days,
+        &HibpBreachSource,
+    );
+
+    println!();
+    println!("{}", "Password Audit".bright_cyan().bold());
+    println!("{}", "─".repeat(80).bright_black());
+
+    let mut flagged_count = 0;
+    for report in reports
+        .iter()
+        .filter(|r| r.is_weak || r.is_common || r.is_reused || r.is_old || r.breach_count.unwrap_or(0) > 0)
+    {
+        flagged_count += 1;
+        println!(
+            "  {} {} — {}",
+            "•".bright_white(),
+            report.service.bright_white().bold(),
+            report.warnings.join(", ").red()
+        );
+    }
+
+    println!("{}", "─".repeat(80).bright_black());
+    println!(
+        "{} of {} credentials need attention",
+        flagged_count,
+        reports.len()
+    );
+    println!(
+        "Health score: {} ({})",
+        health.overall_score,
+        health.score_category()
+    );
+    for recommendation in &health.recommendations {
+        println!("  {} {}", "→".bright_black(), recommendation);
+    }
+
+    let age_stats = &vault_manager.vault().stats.age_stats;
+    if let (Some(min), Some(max)) = (age_stats.min, age_stats.max) {
+        println!();
+        println!("{}", "Password Age (days)".bright_cyan().bold());
+        print!("  min {} / max {}", min, max);
+        if let Some(median) = age_stats.median {
+            print!(" / median {}", median);
+        }
+        if let Some(p90) = age_stats.p90 {
+            print!(" / p90 {}", p90);
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+/// Handle the audit-log command: prints every logged operation in order and
+/// reports whether the hash chain linking them is still intact.
+pub fn handle_audit_log(
+    vault_manager: VaultManager<Locked>,
+    master_password: Option<&str>,
+) -> Result<()> {
+    let vault_manager = unlock_vault(vault_manager, master_password)?;
+    let (entries, chain_result) = vault_manager.audit_log();
+
+    if entries.is_empty() {
+        println!("{}", "No audit log entries yet.".yellow());
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", "Audit Log".bright_cyan().bold());
+    println!("{}", "─".repeat(80).bright_black());
+
+    for entry in entries {
+        let status = if entry.success { "✓".green() } else { "✗".red() };
+        println!(
+            "  {} {} {} {}",
+            status,
+            entry.timestamp.format("%Y-%m-%d %H:%M:%S").to_string().bright_black(),
+            entry.operation.bright_white(),
+            entry
+                .service
+                .as_deref()
+                .map(|s| format!("({})", s))
+                .unwrap_or_default()
+                .bright_black()
+        );
+    }
+
+    println!("{}", "─".repeat(80).bright_black());
+    match chain_result {
+        Ok(()) => println!("{}", "✓ Hash chain verified — no tampering detected".green()),
+        Err(index) => println!(
+            "{} {}",
+            "✗ Hash chain broken at entry".red(),
+            index.to_string().bright_white()
+        ),
+    }
+
+    Ok(())
+}
+
+/// Handle the snapshot command: either freezes a new snapshot of the
+/// vault's current state, or (with `--list`) lists retained generations.
+pub fn handle_snapshot(
+    vault_manager: VaultManager<Locked>,
+    list: bool,
+    master_password: Option<&str>,
+) -> Result<()> {
+    let mut vault_manager = unlock_vault(vault_manager, master_password)?;
+
+    if list {
+        let snapshots = vault_manager.list_snapshots();
+        if snapshots.is_empty() {
+            println!("{}", "No snapshots yet.".yellow());
+            return Ok(());
+        }
+
+        println!();
+        println!("{}", "Snapshots".bright_cyan().bold());
+        println!("{}", "─".repeat(80).bright_black());
+        for snapshot in snapshots {
+            println!(
+                "  {} {} — {} credential(s), {}",
+                "•".bright_green(),
+                snapshot.generation.to_string().bright_white().bold(),
+                snapshot.credentials.len(),
+                snapshot.timestamp.format("%Y-%m-%d %H:%M:%S")
+            );
+        }
+        println!("{}", "─".repeat(80).bright_black());
+        return Ok(());
+    }
+
+    let generation = vault_manager.snapshot()?;
+    println!();
+    println!(
+        "{} {}",
+        "✓ Snapshot frozen as generation".bright_green(),
+        generation.to_string().bright_white()
+    );
+
+    Ok(())
+}
+
+/// Handle the snapshot-rollback command: restores the vault's credentials
+/// and settings from a previously frozen generation.
+pub fn handle_snapshot_rollback(
+    vault_manager: VaultManager<Locked>,
+    generation: u64,
+    master_password: Option<&str>,
+) -> Result<()> {
+    let mut vault_manager = unlock_vault(vault_manager, master_password)?;
+
+    let confirmation = prompt_input(&format!(
+        "Are you sure you want to roll back to snapshot generation {}? (yes/no): ",
+        generation
+    ))?;
+    if confirmation.to_lowercase() != "yes" {
+        println!("{}", "Cancelled.".yellow());
+        return Ok(());
+    }
+
+    vault_manager.rollback_to_snapshot(generation)?;
+
+    println!();
+    println!(
+        "{} {}",
+        "✓ Vault restored to snapshot generation".bright_green(),
+        generation.to_string().bright_white()
+    );
+
+    Ok(())
+}
+
+/// Handle the rollback command: restores the storage backend's rolling
+/// backup, undoing the most recent save.
+pub fn handle_rollback(vault_manager: VaultManager<Locked>) -> Result<()> {
+    if !vault_manager.vault_exists() {
+        return Err(PasswordManagerError::VaultNotFound);
+    }
+
+    vault_manager.rollback()?;
+
+    println!();
+    println!("{}", "✓ Vault rolled back to its last backup.".bright_green());
+
+    Ok(())
+}
+
+/// Handle the recalibrate command: re-runs Argon2id calibration for this
+/// machine and re-encrypts the vault under the new parameters.
+pub fn handle_recalibrate(
+    vault_manager: VaultManager<Locked>,
+    master_password: Option<&str>,
+) -> Result<()> {
+    if !vault_manager.vault_exists() {
+        return Err(PasswordManagerError::VaultNotFound);
+    }
+
+    let password = resolve_secret(master_password, "Master password: ")?;
+    let mut vault_manager = vault_manager.unlock(&password)?;
+    vault_manager.recalibrate(&password)?;
+
+    println!();
+    println!(
+        "{}",
+        "✓ Vault re-calibrated and re-encrypted under new Argon2id parameters.".bright_green()
+    );
+
+    Ok(())
+}
+
+/// Unlock vault, using `master_password` if given (see `resolve_secret`) or
+/// otherwise prompting interactively.
+fn unlock_vault(
+    vault_manager: VaultManager<Locked>,
+    master_password: Option<&str>,
+) -> Result<VaultManager<Unlocked>> {
+    if !vault_manager.vault_exists() {
+        return Err(PasswordManagerError::VaultNotFound);
+    }
+
+    let password = resolve_secret(master_password, "Master password: ")?;
+    vault_manager.unlock(&password)
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use crate::models::Credential;
+
+/// Thread-safe credential store for multi-threaded front-ends (e.g. a sync
+/// daemon running alongside an interactive UI) that would otherwise have to
+/// lock the whole `Vault` for every read.
+///
+/// Modeled on a "credit-only locks" access pattern: many readers can touch
+/// different credentials concurrently, and only a mutating writer excludes
+/// other access to that one credential. The outer `RwLock` is taken only to
+/// add or remove a service name (changing the key set itself); reading or
+/// writing an existing credential's fields takes only that credential's own
+/// lock. Credentials are kept behind `Arc` so a caller can clone the handle
+/// out of a brief outer read lock and then lock just that one credential,
+/// instead of holding the whole map locked for the duration of the access.
+///
+/// This wraps the same `Credential` data a plain `Vault` persists, but isn't
+/// itself serialized — build one from a loaded `Vault`'s credentials with
+/// `from_credentials`, and collect the result back with `into_credentials`
+/// before saving. `VaultManager::import` uses exactly this pattern to merge
+/// a large batch of imported credentials across worker threads.
+pub struct ConcurrentVault {
+    credentials: RwLock<HashMap<String, Arc<RwLock<Credential>>>>,
+    /// `service -> tags` cache, rebuilt on every mutation, so tag queries
+    /// don't need to lock and clone every credential to answer.
+    tag_index: RwLock<HashMap<String, Vec<String>>>,
+}
+
+impl ConcurrentVault {
+    /// Builds a `ConcurrentVault` from a vector of credentials (e.g. loaded
+    /// from a `Vault`).
+    pub fn from_credentials(credentials: Vec<Credential>) -> Self {
+        let mut map = HashMap::with_capacity(credentials.len());
+        let mut tag_index = HashMap::with_capacity(credentials.len());
+        for cred in credentials {
+            tag_index.insert(cred.service.clone(), cred.tags.clone());
+            map.insert(cred.service.clone(), Arc::new(RwLock::new(cred)));
+        }
+        Self {
+            credentials: RwLock::new(map),
+            tag_index: RwLock::new(tag_index),
+        }
+    }
+
+    /// Drains this store back into a plain `Vec<Credential>`, e.g. to store
+    /// into `Vault::credentials` before persisting.
+    pub fn into_credentials(self) -> Vec<Credential> {
+        self.credentials
+            .into_inner()
+            .unwrap()
+            .into_values()
+            .map(|lock| Arc::try_unwrap(lock).map_or_else(|arc| arc.read().unwrap().clone(), |lock| lock.into_inner().unwrap()))
+            .collect()
+    }
+
+    /// Adds a new credential. Takes the outer write lock only long enough to
+    /// insert the key. Returns an error (rather than overwriting) if the
+    /// service already exists — callers merging a batch of credentials from
+    /// multiple threads rely on this to decide a duplicate lost the race.
+    pub fn add_credential(&self, credential: Credential) -> Result<(), String> {
+        let mut map = self.credentials.write().unwrap();
+        if map.contains_key(&credential.service) {
+            return Err(format!("Credential already exists: {}", credential.service));
+        }
+        self.tag_index
+            .write()
+            .unwrap()
+            .insert(credential.service.clone(), credential.tags.clone());
+        map.insert(credential.service.clone(), Arc::new(RwLock::new(credential)));
+        Ok(())
+    }
+
+    /// Removes a credential by service name.
+    pub fn remove_credential(&self, service: &str) -> Result<(), String> {
+        let mut map = self.credentials.write().unwrap();
+        if map.remove(service).is_none() {
+            return Err(format!("Credential not found: {}", service));
+        }
+        self.tag_index.write().unwrap().remove(service);
+        Ok(())
+    }
+
+    /// Returns a clone of the shared handle for a credential. Callers lock it
+    /// themselves (`.read()` / `.write()`) without holding up any other
+    /// credential's access or blocking other readers of the same one.
+    pub fn get_credential(&self, service: &str) -> Option<Arc<RwLock<Credential>>> {
+        self.credentials.read().unwrap().get(service).cloned()
+    }
+
+    /// Updates a credential's tag cache entry after its tags changed via a
+    /// write guard obtained from `get_credential`. Callers that mutate
+    /// `tags` through the guard should call this afterward so `get_by_tag`
+    /// stays in sync without re-scanning every credential.
+    pub fn refresh_tag_index(&self, service: &str) {
+        let Some(handle) = self.get_credential(service) else {
+            return;
+        };
+        let tags = handle.read().unwrap().tags.clone();
+        self.tag_index.write().unwrap().insert(service.to_string(), tags);
+    }
+
+    /// Search credentials by service/username substring. Each matching
+    /// credential is locked for reading only as long as it takes to check
+    /// it, so concurrent searches never block each other.
+    pub fn search(&self, query: &str) -> Vec<Credential> {
+        let query_lower = query.to_lowercase();
+        let handles: Vec<_> = self.credentials.read().unwrap().values().cloned().collect();
+        handles
+            .into_iter()
+            .filter_map(|handle| {
+                let cred = handle.read().unwrap();
+                if cred.service.to_lowercase().contains(&query_lower)
+                    || cred.username.to_lowercase().contains(&query_lower)
+                {
+                    Some(cred.clone())
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Get services tagged with `tag`, served entirely from the tag index —
+    /// no credential needs to be locked or cloned to answer this.
+    pub fn get_by_tag(&self, tag: &str) -> Vec<String> {
+        self.tag_index
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(_, tags)| tags.contains(&tag.to_string()))
+            .map(|(service, _)| service.clone())
+            .collect()
+    }
+
+    /// Find passwords reused across multiple services.
+    pub fn find_reused_passwords(&self) -> HashMap<String, Vec<String>> {
+        let handles: Vec<_> = self.credentials.read().unwrap().values().cloned().collect();
+        let mut password_map: HashMap<String, Vec<String>> = HashMap::new();
+        for handle in handles {
+            let cred = handle.read().unwrap();
+            if !cred.password.is_empty() {
+                password_map
+                    .entry(cred.password.clone())
+                    .or_default()
+                    .push(cred.service.clone());
+            }
+        }
+        password_map
+            .into_iter()
+            .filter(|(_, services)| services.len() > 1)
+            .collect()
+    }
+}
@@ -35,6 +35,9 @@ pub enum PasswordManagerError {
 
     #[error("Clipboard error: {0}")]
     ClipboardError(String),
+
+    #[error("Network error: {0}")]
+    NetworkError(String),
 }
 
 pub type Result<T> = std::result::Result<T, PasswordManagerError>;
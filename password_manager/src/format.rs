@@ -0,0 +1,217 @@
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Result;
+use crate::models::Credential;
+use crate::security::parse_totp_uri;
+
+/// Export/import schema for `VaultManager::export`/`import`. Also the
+/// `--format` value for the CLI's `export`/`import` subcommands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// This tool's own encrypted vault blob — the same format the storage
+    /// backend persists (freshly encrypted under a new nonce), just written
+    /// to an arbitrary path as a backup.
+    Native,
+    /// Bitwarden's plaintext JSON export schema (`{ "items": [...] }`),
+    /// for migrating in or out of Bitwarden.
+    #[value(name = "bitwarden")]
+    BitwardenJson,
+    /// `service,username,password,url,notes,tags,favorite` CSV, for
+    /// spreadsheet-based migration. See `Vault::import_csv`/`export_csv`.
+    Csv,
+}
+
+/// Bitwarden marks a login item with `type: 1` (the other variants are
+/// secure note, card, and identity). This tool only models logins, so
+/// that's the only type it ever writes or reads.
+const BITWARDEN_LOGIN_TYPE: u8 = 1;
+
+/// Bitwarden's custom-field `type: 0` ("text"). `1` (hidden) and `2`
+/// (boolean) also exist, but `custom_fields` is just `String -> String`,
+/// so every field this tool writes is plain text.
+const BITWARDEN_FIELD_TYPE_TEXT: u8 = 0;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BitwardenExport {
+    items: Vec<BitwardenItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BitwardenItem {
+    #[serde(rename = "type")]
+    item_type: u8,
+    name: String,
+    login: BitwardenLogin,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    fields: Vec<BitwardenField>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BitwardenLogin {
+    username: String,
+    password: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    totp: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BitwardenField {
+    name: String,
+    value: String,
+    #[serde(rename = "type")]
+    field_type: u8,
+}
+
+/// Serializes credentials into Bitwarden's `{ "items": [...] }` export
+/// schema, including each credential's TOTP secret and `custom_fields`.
+pub fn credentials_to_bitwarden_json(credentials: &[Credential]) -> Result<String> {
+    let items = credentials
+        .iter()
+        .map(|c| BitwardenItem {
+            item_type: BITWARDEN_LOGIN_TYPE,
+            name: c.service.clone(),
+            login: BitwardenLogin {
+                username: c.username.clone(),
+                password: c.password.clone(),
+                totp: c.totp_secret.clone(),
+            },
+            notes: c.notes.clone(),
+            fields: c
+                .custom_fields
+                .iter()
+                .map(|(name, value)| BitwardenField {
+                    name: name.clone(),
+                    value: value.clone(),
+                    field_type: BITWARDEN_FIELD_TYPE_TEXT,
+                })
+                .collect(),
+        })
+        .collect();
+
+    Ok(serde_json::to_string_pretty(&BitwardenExport { items })?)
+}
+
+/// Parses a Bitwarden `{ "items": [...] }` export into credentials, skipping
+/// any non-login items (secure notes, cards, identities). Each item's
+/// `fields` become `custom_fields`, and `login.totp` is normalized down to
+/// a bare base32 secret before being stored as `totp_secret`.
+pub fn bitwarden_json_to_credentials(data: &str) -> Result<Vec<Credential>> {
+    let export: BitwardenExport = serde_json::from_str(data)?;
+
+    Ok(export
+        .items
+        .into_iter()
+        .filter(|item| item.item_type == BITWARDEN_LOGIN_TYPE)
+        .map(|item| {
+            let mut credential =
+                Credential::new(item.name, item.login.username, item.login.password, item.notes);
+            credential.custom_fields = item
+                .fields
+                .into_iter()
+                .map(|f| (f.name, f.value))
+                .collect();
+            credential.totp_secret = item.login.totp.as_deref().and_then(normalize_totp_secret);
+            credential
+        })
+        .collect())
+}
+
+/// Normalizes a Bitwarden `login.totp` value down to a bare base32 secret.
+/// Bitwarden (and other authenticators) sometimes store the full
+/// `otpauth://totp/...` URI there instead of just the secret; when it's an
+/// `otpauth://` URI, this unwraps it with `parse_totp_uri` and keeps only
+/// the secret, otherwise the value is already a bare secret and is used
+/// as-is.
+fn normalize_totp_secret(totp: &str) -> Option<String> {
+    let totp = totp.trim();
+    if totp.is_empty() {
+        return None;
+    }
+    if totp.starts_with("otpauth://") {
+        parse_totp_uri(totp).ok().map(|entry| entry.secret)
+    } else {
+        Some(totp.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::{analyze_vault_health, generate_password_reports};
+    use crate::models::{Credential, Vault};
+    use crate::security::{generate_totp_secret, generate_totp_uri};
+
+    fn sample_vault() -> (Vault, String) {
+        let mut vault = Vault::new();
+
+        let mut with_totp = Credential::new(
+            "github.com".to_string(),
+            "octocat".to_string(),
+            "correct-horse-battery-staple".to_string(),
+            Some("work account".to_string()),
+        );
+        with_totp
+            .custom_fields
+            .insert("recovery email".to_string(), "octo@example.com".to_string());
+        with_totp.totp_secret = Some(generate_totp_secret());
+        vault.add_credential(with_totp).unwrap();
+
+        let uri_secret = generate_totp_secret();
+        let mut uri_totp = Credential::new(
+            "example.com".to_string(),
+            "alice".to_string(),
+            "hunter2".to_string(),
+            None,
+        );
+        // Some authenticators store the whole otpauth:// URI in the TOTP
+        // field instead of the bare secret; normalize_totp_secret should
+        // unwrap it back down to just the secret on import.
+        uri_totp.totp_secret = Some(generate_totp_uri(&uri_secret, "alice", "example.com"));
+        vault.add_credential(uri_totp).unwrap();
+
+        let plain = Credential::new(
+            "plain.example".to_string(),
+            "bob".to_string(),
+            "password".to_string(),
+            None,
+        );
+        vault.add_credential(plain).unwrap();
+
+        (vault, uri_secret)
+    }
+
+    #[test]
+    fn test_bitwarden_round_trip_preserves_totp_and_custom_fields() {
+        let (vault, uri_secret) = sample_vault();
+
+        let json = credentials_to_bitwarden_json(&vault.credentials).unwrap();
+        let imported = bitwarden_json_to_credentials(&json).unwrap();
+
+        let mut roundtripped = Vault::new();
+        for credential in imported {
+            roundtripped.add_credential(credential).unwrap();
+        }
+
+        let github_before = vault.get_credential("github.com").unwrap();
+        let github_after = roundtripped.get_credential("github.com").unwrap();
+        assert_eq!(github_before.totp_secret, github_after.totp_secret);
+        assert_eq!(github_before.custom_fields, github_after.custom_fields);
+
+        // The otpauth:// URI should have been normalized down to the bare
+        // secret it was generated from.
+        let uri_before = vault.get_credential("example.com").unwrap();
+        assert!(uri_before.totp_secret.as_deref().unwrap().starts_with("otpauth://"));
+        let uri_after = roundtripped.get_credential("example.com").unwrap();
+        assert_eq!(uri_after.totp_secret.as_deref(), Some(uri_secret.as_str()));
+
+        let health_before = analyze_vault_health(&vault, 90);
+        let health_after = analyze_vault_health(&roundtripped, 90);
+        assert_eq!(format!("{:?}", health_before), format!("{:?}", health_after));
+
+        let reports_before = generate_password_reports(&vault, 90);
+        let reports_after = generate_password_reports(&roundtripped, 90);
+        assert_eq!(format!("{:?}", reports_before), format!("{:?}", reports_after));
+    }
+}
@@ -0,0 +1,185 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use argon2::{Argon2, Block, Memory};
+
+use crate::crypto::KdfParams;
+use crate::errors::{PasswordManagerError, Result};
+use crate::locked_bytes::LockedBytes;
+
+/// A single key-derivation job submitted to the `Hasher` pool. `reply`
+/// carries the derived key back to whichever caller is awaiting it.
+struct HashRequest {
+    password: Vec<u8>,
+    salt: Vec<u8>,
+    params: KdfParams,
+    reply: Sender<Result<LockedBytes>>,
+}
+
+/// Pool of named worker threads that run Argon2id derivations off the
+/// calling thread, so an unlock or bulk re-encryption doesn't freeze the
+/// interactive prompt. Each worker pre-allocates its own `max_m_cost`-sized
+/// `Block` buffer once at startup and reuses it for every job it handles,
+/// instead of letting Argon2 allocate a fresh multi-megabyte working set per
+/// derive. Jobs are handed out over an MPSC channel shared by all workers,
+/// so however many are idle pick up the next queued job. See
+/// `VaultManager::recalibrate`, which uses `submit_batch` to run the
+/// old-params verification derive and the new-params derive concurrently
+/// instead of back to back.
+pub struct Hasher {
+    job_tx: Option<Sender<HashRequest>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl Hasher {
+    /// Spawns `worker_count` named worker threads (`hasher-0`, `hasher-1`,
+    /// ...). `max_m_cost` bounds the working-memory buffer each worker
+    /// pre-allocates, in KiB — it should be at least as large as the
+    /// largest `KdfParams::m_cost` this pool will ever be asked to run.
+    pub fn new(worker_count: usize, max_m_cost: u32) -> Self {
+        let (job_tx, job_rx) = mpsc::channel::<HashRequest>();
+        let job_rx = Arc::new(Mutex::new(job_rx));
+
+        let workers = (0..worker_count.max(1))
+            .map(|i| {
+                let job_rx = Arc::clone(&job_rx);
+                thread::Builder::new()
+                    .name(format!("hasher-{i}"))
+                    .spawn(move || Self::worker_loop(job_rx, max_m_cost))
+                    .expect("failed to spawn hasher worker thread")
+            })
+            .collect();
+
+        Self {
+            job_tx: Some(job_tx),
+            workers,
+        }
+    }
+
+    fn worker_loop(job_rx: Arc<Mutex<Receiver<HashRequest>>>, max_m_cost: u32) {
+        let mut blocks = vec![Block::default(); max_m_cost as usize];
+
+        loop {
+            let request = {
+                let rx = job_rx.lock().unwrap();
+                rx.recv()
+            };
+            let Ok(request) = request else {
+                // Every `Sender` (the pool's `job_tx` plus any in-flight
+                // clones) has been dropped; nothing left to do.
+                break;
+            };
+
+            let result = hash_with_memory(
+                &request.password,
+                &request.salt,
+                request.params,
+                &mut blocks,
+            );
+            let _ = request.reply.send(result);
+        }
+    }
+
+    /// Derives a single key under `params`, blocking the calling thread
+    /// until a worker finishes it — a caller that wants to stay responsive
+    /// should call this from a background thread or poll `try_recv` on its
+    /// own channel instead of calling it inline on the UI thread.
+    pub fn submit(
+        &self,
+        password: &str,
+        salt: &[u8],
+        params: KdfParams,
+    ) -> Result<LockedBytes> {
+        let reply_rx = self.dispatch(password, salt.to_vec(), params)?;
+        reply_rx.recv().map_err(|_| {
+            PasswordManagerError::EncryptionError(
+                "hasher worker dropped without replying".to_string(),
+            )
+        })?
+    }
+
+    /// Submits a batch of `(password, salt, params)` jobs — each may use
+    /// its own `KdfParams`, e.g. one verifying a password under the vault's
+    /// current parameters while another derives a fresh key under new ones
+    /// — fanning them out across whichever workers are idle, and collects
+    /// every result in submission order.
+    pub fn submit_batch(
+        &self,
+        jobs: &[(String, Vec<u8>, KdfParams)],
+    ) -> Vec<Result<LockedBytes>> {
+        let pending: Vec<_> = jobs
+            .iter()
+            .map(|(password, salt, params)| self.dispatch(password, salt.clone(), *params))
+            .collect();
+
+        pending
+            .into_iter()
+            .map(|reply_rx| {
+                reply_rx?.recv().map_err(|_| {
+                    PasswordManagerError::EncryptionError(
+                        "hasher worker dropped without replying".to_string(),
+                    )
+                })?
+            })
+            .collect()
+    }
+
+    fn dispatch(
+        &self,
+        password: &str,
+        salt: Vec<u8>,
+        params: KdfParams,
+    ) -> Result<Receiver<Result<LockedBytes>>> {
+        let (reply_tx, reply_rx) = mpsc::channel();
+        self.job_tx
+            .as_ref()
+            .expect("job_tx is only taken in Drop")
+            .send(HashRequest {
+                password: password.as_bytes().to_vec(),
+                salt,
+                params,
+                reply: reply_tx,
+            })
+            .map_err(|_| {
+                PasswordManagerError::EncryptionError("hasher pool is shut down".to_string())
+            })?;
+        Ok(reply_rx)
+    }
+}
+
+impl Drop for Hasher {
+    fn drop(&mut self) {
+        // Drop the sender first so every worker's `recv()` returns `Err` and
+        // the loop exits, then join so the pool's memory buffers are freed
+        // before `Hasher` itself goes away.
+        self.job_tx.take();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Runs one Argon2id derivation reusing a pre-allocated `Block` buffer
+/// instead of letting `Argon2` allocate its own working memory.
+fn hash_with_memory(
+    password: &[u8],
+    salt: &[u8],
+    params: KdfParams,
+    blocks: &mut [Block],
+) -> Result<LockedBytes> {
+    let argon2 = Argon2::new(
+        argon2::Algorithm::Argon2id,
+        argon2::Version::V0x13,
+        argon2::Params::new(params.m_cost, params.t_cost, params.p_cost, Some(32))
+            .map_err(|e| PasswordManagerError::EncryptionError(e.to_string()))?,
+    );
+
+    let mut key = LockedBytes::new(vec![0u8; 32]).map_err(PasswordManagerError::EncryptionError)?;
+    let memory = Memory::new(&mut blocks[..params.m_cost as usize]);
+    argon2
+        .hash_password_into_with_memory(password, salt, key.as_mut_slice(), memory)
+        .map_err(|e| PasswordManagerError::EncryptionError(e.to_string()))?;
+
+    Ok(key)
+}
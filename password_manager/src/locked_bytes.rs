@@ -0,0 +1,107 @@
+use zeroize::Zeroize;
+
+/// A byte buffer locked in physical RAM via `region::lock` (so the OS
+/// cannot swap it to disk) and zeroed on drop. Used for secrets whose
+/// lifetime in memory we want to bound as tightly as their lifetime on
+/// disk — decoded TOTP seeds, derived master keys.
+///
+/// Locking a `Vec`'s backing allocation is only sound as long as that
+/// allocation never moves, so `LockedBytes` never exposes a way to grow or
+/// shrink its buffer after construction.
+pub struct LockedBytes {
+    data: Vec<u8>,
+    guard: Option<region::LockGuard>,
+}
+
+impl LockedBytes {
+    /// Locks `data`'s backing memory in place and wraps it. If locking
+    /// fails — insufficient privileges, a locked-pages limit, or a platform
+    /// `region` doesn't support — and the `mlock-fallback` feature is
+    /// enabled, falls back to an unlocked (but still zeroize-on-drop)
+    /// buffer and logs a warning instead of failing outright. Without that
+    /// feature, a lock failure is returned as an error.
+    pub fn new(data: Vec<u8>) -> Result<Self, String> {
+        match region::lock(data.as_ptr(), data.len().max(1)) {
+            Ok(guard) => Ok(LockedBytes {
+                data,
+                guard: Some(guard),
+            }),
+            Err(e) => {
+                #[cfg(feature = "mlock-fallback")]
+                {
+                    eprintln!(
+                        "Warning: failed to lock secret memory in RAM ({e}); \
+                         falling back to an unlocked, zeroize-on-drop buffer"
+                    );
+                    Ok(LockedBytes { data, guard: None })
+                }
+                #[cfg(not(feature = "mlock-fallback"))]
+                {
+                    Err(format!("failed to lock secret memory in RAM: {e}"))
+                }
+            }
+        }
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        &mut self.data
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
+
+impl std::ops::Deref for LockedBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl std::ops::DerefMut for LockedBytes {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.data
+    }
+}
+
+impl Drop for LockedBytes {
+    fn drop(&mut self) {
+        // Zeroize while still locked, then let `guard` drop (unlocking the
+        // now-zeroed page) as the struct's fields are dropped in order.
+        self.data.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locked_bytes_round_trip() {
+        let locked = LockedBytes::new(vec![1, 2, 3, 4]).unwrap();
+        assert_eq!(locked.as_slice(), &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_locked_bytes_deref() {
+        let locked = LockedBytes::new(vec![9, 9, 9]).unwrap();
+        assert_eq!(&*locked, &[9, 9, 9]);
+    }
+
+    #[test]
+    fn test_locked_bytes_mutation() {
+        let mut locked = LockedBytes::new(vec![0, 0, 0]).unwrap();
+        locked.as_mut_slice().copy_from_slice(&[1, 2, 3]);
+        assert_eq!(locked.as_slice(), &[1, 2, 3]);
+    }
+}
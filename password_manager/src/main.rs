@@ -2,15 +2,25 @@ use clap::Parser;
 use colored::*;
 use std::process;
 
+mod analytics;
 mod cli;
+mod concurrent;
 mod crypto;
 mod errors;
+mod format;
+mod hasher;
+mod locked_bytes;
 mod models;
+#[allow(dead_code)]
+mod security;
+mod storage;
 mod vault;
 
 use cli::{
-    handle_add, handle_generate, handle_get, handle_init, handle_list, handle_remove,
-    handle_search, handle_update, Cli, Commands,
+    handle_add, handle_audit, handle_audit_log, handle_change_master_password, handle_check,
+    handle_export, handle_generate, handle_get, handle_import, handle_init, handle_list,
+    handle_recalibrate, handle_remove, handle_rollback, handle_search, handle_snapshot,
+    handle_snapshot_rollback, handle_totp, handle_update, resolve_storage, Cli, Commands,
 };
 use vault::VaultManager;
 
@@ -18,82 +28,67 @@ fn main() {
     // Parse command line arguments
     let cli = Cli::parse();
 
-    // Get vault path
-    let vault_path = match VaultManager::default_vault_path() {
-        Ok(path) => path,
+    // Build the storage backend selected by --storage (file by default)
+    let storage = match resolve_storage(&cli) {
+        Ok(storage) => storage,
         Err(e) => {
             eprintln!("{} {}", "Error:".bright_red(), e);
             process::exit(1);
         }
     };
 
-    // Create vault manager
-    let mut vault_manager = VaultManager::new(vault_path);
+    // Create vault manager (locked until a command unlocks it)
+    let vault_manager = VaultManager::with_storage(storage);
 
     // Execute command
+    let master_password = cli.master_password.as_deref();
+
     let result = match cli.command {
-        Commands::Init => handle_init(&mut vault_manager),
+        Commands::Init => handle_init(vault_manager, master_password),
 
         Commands::Add {
             service,
             username,
             generate,
             length,
-        } => handle_add(&mut vault_manager, service, username, generate, length),
-
-        Commands::Get { service, show } => {
-            if let Err(e) = cli::prompt_master_password("Master password: ")
-                .and_then(|password| vault_manager.unlock(&password))
-            {
-                eprintln!("{} {}", "Error:".bright_red(), e);
-                process::exit(1);
-            }
-            handle_get(&vault_manager, &service, show)
-        }
+            password,
+            totp_secret,
+        } => handle_add(
+            vault_manager,
+            service,
+            username,
+            generate,
+            length,
+            password,
+            totp_secret,
+            master_password,
+        ),
 
-        Commands::List => {
-            if let Err(e) = cli::prompt_master_password("Master password: ")
-                .and_then(|password| vault_manager.unlock(&password))
-            {
-                eprintln!("{} {}", "Error:".bright_red(), e);
-                process::exit(1);
-            }
-            handle_list(&vault_manager)
-        }
+        Commands::Get { service, show } => handle_get(vault_manager, &service, show, master_password),
 
-        Commands::Search { query } => {
-            if let Err(e) = cli::prompt_master_password("Master password: ")
-                .and_then(|password| vault_manager.unlock(&password))
-            {
-                eprintln!("{} {}", "Error:".bright_red(), e);
-                process::exit(1);
-            }
-            handle_search(&vault_manager, &query)
+        Commands::List { filter, regex } => {
+            handle_list(vault_manager, filter, regex, master_password)
         }
 
+        Commands::Search { query } => handle_search(vault_manager, &query, master_password),
+
         Commands::Update {
             service,
             generate,
             length,
-        } => {
-            if let Err(e) = cli::prompt_master_password("Master password: ")
-                .and_then(|password| vault_manager.unlock(&password))
-            {
-                eprintln!("{} {}", "Error:".bright_red(), e);
-                process::exit(1);
-            }
-            handle_update(&mut vault_manager, &service, generate, length)
-        }
+            password,
+            totp_secret,
+        } => handle_update(
+            vault_manager,
+            &service,
+            generate,
+            length,
+            password,
+            totp_secret,
+            master_password,
+        ),
 
-        Commands::Remove { service } => {
-            if let Err(e) = cli::prompt_master_password("Master password: ")
-                .and_then(|password| vault_manager.unlock(&password))
-            {
-                eprintln!("{} {}", "Error:".bright_red(), e);
-                process::exit(1);
-            }
-            handle_remove(&mut vault_manager, &service)
-        }
+        Commands::Remove { service } => handle_remove(vault_manager, &service, master_password),
 
         Commands::Generate {
             length,
@@ -102,6 +97,38 @@ fn main() {
             no_numbers,
             no_symbols,
         } => handle_generate(length, no_uppercase, no_lowercase, no_numbers, no_symbols),
+
+        Commands::Export { path, format } => {
+            handle_export(vault_manager, &path, format, master_password)
+        }
+
+        Commands::Import { path, format } => {
+            handle_import(vault_manager, &path, format, master_password)
+        }
+
+        Commands::ChangeMasterPassword => {
+            handle_change_master_password(vault_manager, master_password)
+        }
+
+        Commands::Totp { service } => handle_totp(vault_manager, &service, master_password),
+
+        Commands::Check { service } => handle_check(vault_manager, service, master_password),
+
+        Commands::Audit { max_age_days } => {
+            handle_audit(vault_manager, max_age_days, master_password)
+        }
+
+        Commands::AuditLog => handle_audit_log(vault_manager, master_password),
+
+        Commands::Snapshot { list } => handle_snapshot(vault_manager, list, master_password),
+
+        Commands::SnapshotRollback { generation } => {
+            handle_snapshot_rollback(vault_manager, generation, master_password)
+        }
+
+        Commands::Rollback => handle_rollback(vault_manager),
+
+        Commands::Recalibrate => handle_recalibrate(vault_manager, master_password),
     };
 
     // Handle errors
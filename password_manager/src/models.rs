@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::io::{BufRead, Write};
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
 /// Entry in password history
@@ -117,17 +118,29 @@ impl Credential {
     }
 }
 
-/// Encrypted vault data stored on disk
+/// Encrypted vault data stored on disk. Doubles as the vault's
+/// self-describing header: `algorithm` and `kdf_params` record which cipher
+/// and Argon2id settings produced `ciphertext`, so a vault can be opened
+/// regardless of which combination originally wrote it. Both fields default
+/// (via `#[serde(default)]`) to what this crate always used before they
+/// existed — AES-256-GCM and the fixed Argon2 constants — so older vaults
+/// on disk still open correctly.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EncryptedVault {
     /// Salt used for key derivation (Argon2)
     pub salt: Vec<u8>,
-    /// Nonce for AES-GCM encryption
+    /// Nonce for `algorithm` (12 bytes for AES-GCM, 24 for XChaCha20-Poly1305)
     pub nonce: Vec<u8>,
     /// Encrypted credential data
     pub ciphertext: Vec<u8>,
     /// Version for future compatibility
     pub version: u32,
+    /// Which cipher produced `ciphertext`.
+    #[serde(default)]
+    pub algorithm: crate::crypto::CipherAlgorithm,
+    /// Argon2id parameters used to derive the key that encrypted this vault.
+    #[serde(default)]
+    pub kdf_params: crate::crypto::KdfParams,
 }
 
 /// Vault settings and configuration
@@ -159,6 +172,63 @@ pub struct VaultStats {
     pub reused_passwords: usize,
     pub old_passwords: usize,
     pub last_backup: Option<DateTime<Utc>>,
+    /// Percentile summary of `password_age_days()` across all credentials.
+    pub age_stats: AgeStats,
+}
+
+/// Age threshold (in days) past which a password counts as "old" for
+/// `VaultStats::old_passwords`, matching the default used elsewhere in the
+/// analytics report.
+const OLD_PASSWORD_THRESHOLD_DAYS: i64 = 90;
+
+/// Percentile summary of password age in days, modeled on the
+/// prioritization-fee percentile summary used in block analytics: each
+/// percentile is `sorted[len * pct / 100]` of the sorted age vector, and
+/// the higher percentiles are only populated once there's enough of a
+/// sample (`len > 1`) for them to mean anything.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+pub struct AgeStats {
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+    pub median: Option<i64>,
+    pub p75: Option<i64>,
+    pub p90: Option<i64>,
+    pub p95: Option<i64>,
+}
+
+impl AgeStats {
+    /// Computes age percentiles from an already-sorted ascending slice of
+    /// `password_age_days()` values.
+    fn from_sorted_ages(sorted: &[i64]) -> Self {
+        if sorted.is_empty() {
+            return Self::default();
+        }
+
+        let percentile = |pct: usize| sorted[sorted.len() * pct / 100];
+
+        let min = Some(sorted[0]);
+        let max = Some(sorted[sorted.len() - 1]);
+
+        if sorted.len() > 1 {
+            Self {
+                min,
+                max,
+                median: Some(percentile(50)),
+                p75: Some(percentile(75)),
+                p90: Some(percentile(90)),
+                p95: Some(percentile(95)),
+            }
+        } else {
+            Self {
+                min,
+                max,
+                median: None,
+                p75: None,
+                p90: None,
+                p95: None,
+            }
+        }
+    }
 }
 
 /// In-memory decrypted vault
@@ -169,15 +239,68 @@ pub struct Vault {
     pub stats: VaultStats,
     #[serde(default)]
     pub audit_log: Vec<AuditLogEntry>,
+    /// Hash chain anchor for `audit_log[0]`'s `prev_hash`. All-zero until
+    /// the log is first trimmed, at which point it becomes the `entry_hash`
+    /// of the most recently dropped entry, so the surviving chain still has
+    /// something real to verify its back-link against.
+    #[serde(default)]
+    pub audit_chain_checkpoint: [u8; 32],
+    /// Ring of the last `settings.backup_count` snapshots, most recent last.
+    #[serde(default)]
+    pub snapshots: Vec<VaultSnapshot>,
+    /// Generation number the next `freeze()` will assign.
+    #[serde(default)]
+    pub next_generation: u64,
 }
 
-/// Audit log entry
+/// An immutable, frozen copy of a vault's credentials and settings, tagged
+/// with a monotonically increasing generation number — mirroring how each
+/// frozen bank in a bank-lifecycle points back to its parent, each snapshot
+/// references `parent_generation` so `Vault::snapshots()` reads as a lineage.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultSnapshot {
+    pub generation: u64,
+    pub parent_generation: Option<u64>,
+    pub timestamp: DateTime<Utc>,
+    pub credentials: Vec<Credential>,
+    pub settings: VaultSettings,
+}
+
+/// Audit log entry. Chained like the bank-lifecycle "each bank points back
+/// to its parent" pattern: every entry's `prev_hash` is the previous
+/// entry's `entry_hash`, so editing, reordering, or deleting an entry in
+/// place breaks the chain and is caught by `Vault::verify_audit_chain`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuditLogEntry {
     pub timestamp: DateTime<Utc>,
     pub operation: String,
     pub service: Option<String>,
     pub success: bool,
+    /// `entry_hash` of the entry immediately before this one (all-zero for
+    /// the vault's very first logged operation).
+    pub prev_hash: [u8; 32],
+    /// `sha256(prev_hash || timestamp || operation || service || success)`.
+    pub entry_hash: [u8; 32],
+}
+
+/// Hashes one audit log entry's fields against `prev_hash`, in the exact
+/// byte order `sha256(prev_hash || timestamp || operation || service ||
+/// success)` — shared by both the entry that creates the hash and
+/// `verify_audit_chain`, which recomputes it.
+fn compute_entry_hash(
+    prev_hash: &[u8; 32],
+    timestamp: &DateTime<Utc>,
+    operation: &str,
+    service: &Option<String>,
+    success: bool,
+) -> [u8; 32] {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(prev_hash);
+    buf.extend_from_slice(timestamp.to_rfc3339().as_bytes());
+    buf.extend_from_slice(operation.as_bytes());
+    buf.extend_from_slice(service.as_deref().unwrap_or("").as_bytes());
+    buf.push(success as u8);
+    crate::crypto::sha256(&buf)
 }
 
 impl Drop for Vault {
@@ -192,27 +315,127 @@ impl Vault {
         Self::default()
     }
 
-    /// Log an operation to the audit log
-    #[allow(dead_code)]
+    /// Log an operation to the audit log, chaining it onto the previous
+    /// entry's hash (or `audit_chain_checkpoint` for the very first entry).
     pub fn log_operation(&mut self, operation: String, service: Option<String>, success: bool) {
+        let prev_hash = self
+            .audit_log
+            .last()
+            .map(|e| e.entry_hash)
+            .unwrap_or(self.audit_chain_checkpoint);
+        let timestamp = Utc::now();
+        let entry_hash = compute_entry_hash(&prev_hash, &timestamp, &operation, &service, success);
+
         self.audit_log.push(AuditLogEntry {
-            timestamp: Utc::now(),
+            timestamp,
             operation,
             service,
             success,
+            prev_hash,
+            entry_hash,
         });
 
-        // Keep only last 1000 entries
+        // Keep only last 1000 entries, re-anchoring the checkpoint to the
+        // dropped entry's hash so the surviving chain still verifies.
         if self.audit_log.len() > 1000 {
-            self.audit_log.remove(0);
+            let removed = self.audit_log.remove(0);
+            self.audit_chain_checkpoint = removed.entry_hash;
         }
     }
 
-    /// Update vault statistics
-    #[allow(dead_code)]
+    /// Walks the audit log recomputing each entry's hash and back-link.
+    /// Returns the index of the first entry that doesn't match, or `Ok(())`
+    /// if the whole chain is intact.
+    pub fn verify_audit_chain(&self) -> Result<(), usize> {
+        let mut expected_prev = self.audit_chain_checkpoint;
+        for (i, entry) in self.audit_log.iter().enumerate() {
+            if entry.prev_hash != expected_prev {
+                return Err(i);
+            }
+            let expected_hash = compute_entry_hash(
+                &entry.prev_hash,
+                &entry.timestamp,
+                &entry.operation,
+                &entry.service,
+                entry.success,
+            );
+            if expected_hash != entry.entry_hash {
+                return Err(i);
+            }
+            expected_prev = entry.entry_hash;
+        }
+        Ok(())
+    }
+
+    /// Captures an immutable snapshot of the current credentials and
+    /// settings, tagged with the next generation number and pointing back
+    /// at the previous snapshot's generation. Keeps only the last
+    /// `settings.backup_count` snapshots, dropping the oldest once the ring
+    /// is full.
+    pub fn freeze(&mut self) -> VaultSnapshot {
+        let generation = self.next_generation;
+        self.next_generation += 1;
+
+        let snapshot = VaultSnapshot {
+            generation,
+            parent_generation: self.snapshots.last().map(|s| s.generation),
+            timestamp: Utc::now(),
+            credentials: self.credentials.clone(),
+            settings: self.settings.clone(),
+        };
+
+        self.snapshots.push(snapshot.clone());
+        while self.snapshots.len() > self.settings.backup_count {
+            self.snapshots.remove(0);
+        }
+
+        snapshot
+    }
+
+    /// Lists available snapshot generations, oldest first.
+    pub fn snapshots(&self) -> &[VaultSnapshot] {
+        &self.snapshots
+    }
+
+    /// Restores credentials and settings from a chosen snapshot generation,
+    /// logging the rollback to the audit log. Fails if that generation has
+    /// already been evicted from the ring (or never existed).
+    pub fn rollback(&mut self, generation: u64) -> Result<(), String> {
+        let snapshot = self
+            .snapshots
+            .iter()
+            .find(|s| s.generation == generation)
+            .ok_or_else(|| format!("Snapshot generation {} not found", generation))?
+            .clone();
+
+        self.credentials = snapshot.credentials;
+        self.settings = snapshot.settings;
+        self.log_operation(format!("rollback to generation {}", generation), None, true);
+        Ok(())
+    }
+
+    /// Update vault statistics in one pass: credential count, weak/old
+    /// password counts, and the age percentile dashboard (`AgeStats`).
     pub fn update_stats(&mut self) {
         self.stats.total_credentials = self.credentials.len();
-        // Other stats will be calculated on-demand
+
+        let mut weak_passwords = 0;
+        let mut ages: Vec<i64> = Vec::with_capacity(self.credentials.len());
+        for cred in &self.credentials {
+            if crate::security::is_weak_password(&cred.password) {
+                weak_passwords += 1;
+            }
+            ages.push(cred.password_age_days());
+        }
+        self.stats.weak_passwords = weak_passwords;
+        self.stats.old_passwords = self
+            .credentials
+            .iter()
+            .filter(|c| c.is_old(OLD_PASSWORD_THRESHOLD_DAYS))
+            .count();
+
+        ages.sort_unstable();
+        self.stats.age_stats = AgeStats::from_sorted_ages(&ages);
     }
 
     pub fn add_credential(&mut self, credential: Credential) -> Result<(), String> {
@@ -288,7 +511,6 @@ impl Vault {
     }
 
     /// Find reused passwords
-    #[allow(dead_code)]
     pub fn find_reused_passwords(&self) -> HashMap<String, Vec<String>> {
         let mut password_map: HashMap<String, Vec<String>> = HashMap::new();
 
@@ -309,7 +531,6 @@ impl Vault {
     }
 
     /// Find old passwords (older than threshold_days)
-    #[allow(dead_code)]
     pub fn find_old_passwords(&self, threshold_days: i64) -> Vec<&Credential> {
         self.credentials
             .iter()
@@ -329,6 +550,168 @@ impl Vault {
         tags.dedup();
         tags
     }
+
+    /// Bulk-import credentials from a `service,username,password,url,notes,tags,favorite`
+    /// CSV stream (header row required, `tags` is `;`-joined). Transactional: every row
+    /// is parsed and checked for duplicates before anything is added, so a malformed or
+    /// duplicate row reports its 1-based line number instead of partially importing.
+    pub fn import_csv<R: BufRead>(&mut self, reader: R) -> Result<usize, Vec<(usize, String)>> {
+        let mut lines = reader.lines();
+
+        // Header row: establishes column order, but isn't otherwise validated —
+        // callers are expected to export/import in the same shape this writes.
+        if lines.next().is_none() {
+            return Ok(0);
+        }
+
+        let mut errors = Vec::new();
+        let mut parsed = Vec::new();
+        let mut seen_services: Vec<String> = Vec::new();
+
+        for (i, line) in lines.enumerate() {
+            let line_no = i + 2; // 1-based, plus the header row
+            let line = match line {
+                Ok(l) => l,
+                Err(e) => {
+                    errors.push((line_no, format!("Failed to read row: {}", e)));
+                    continue;
+                }
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match parse_credential_csv_row(&line) {
+                Ok(credential) => {
+                    if self.get_credential(&credential.service).is_some()
+                        || seen_services.contains(&credential.service)
+                    {
+                        errors.push((
+                            line_no,
+                            format!("Credential already exists: {}", credential.service),
+                        ));
+                    } else {
+                        seen_services.push(credential.service.clone());
+                        parsed.push(credential);
+                    }
+                }
+                Err(e) => errors.push((line_no, e)),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let count = parsed.len();
+        for credential in parsed {
+            self.add_credential(credential)
+                .expect("duplicates already ruled out above");
+        }
+        Ok(count)
+    }
+
+    /// Export credentials as `service,username,password,url,notes,tags,favorite` CSV.
+    /// With `safe: true`, the `password` column is left blank so the export is a
+    /// structure-only inventory that's safe to share.
+    pub fn export_csv<W: Write>(&self, writer: &mut W, safe: bool) -> std::io::Result<()> {
+        writeln!(writer, "service,username,password,url,notes,tags,favorite")?;
+        for cred in &self.credentials {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{}",
+                csv_escape(&cred.service),
+                csv_escape(&cred.username),
+                if safe {
+                    String::new()
+                } else {
+                    csv_escape(&cred.password)
+                },
+                csv_escape(cred.url.as_deref().unwrap_or("")),
+                csv_escape(cred.notes.as_deref().unwrap_or("")),
+                csv_escape(&cred.tags.join(";")),
+                cred.favorite,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses one `service,username,password,url,notes,tags,favorite` CSV data row into a
+/// `Credential`, building it via `Credential::new` and applying the remaining columns
+/// as extras. Returns a human-readable error (without a line number — the caller adds
+/// that) on a malformed row.
+fn parse_credential_csv_row(line: &str) -> Result<Credential, String> {
+    let fields = parse_csv_fields(line);
+    if fields.len() < 3 {
+        return Err(format!(
+            "Expected at least service,username,password (got {} column(s))",
+            fields.len()
+        ));
+    }
+
+    let service = fields[0].clone();
+    let username = fields[1].clone();
+    let password = fields[2].clone();
+    if service.is_empty() {
+        return Err("Missing service name".to_string());
+    }
+
+    let url = fields.get(3).filter(|s| !s.is_empty()).cloned();
+    let notes = fields.get(4).filter(|s| !s.is_empty()).cloned();
+    let tags = fields
+        .get(5)
+        .map(|s| {
+            s.split(';')
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    let favorite = fields
+        .get(6)
+        .map(|s| s.eq_ignore_ascii_case("true") || s == "1")
+        .unwrap_or(false);
+
+    let mut credential = Credential::new(service, username, password, notes);
+    credential.url = url;
+    credential.tags = tags;
+    credential.favorite = favorite;
+    Ok(credential)
+}
+
+/// Splits one CSV row into fields, honoring `"`-quoted fields (with `""` as an
+/// escaped quote) so values may contain literal commas.
+fn parse_csv_fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => {
+                fields.push(std::mem::take(&mut field));
+            }
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Quotes a CSV field if it contains a comma, quote, or newline.
+fn csv_escape(value: &str) -> String {
+    if value.contains(',') || value.contains('"') || value.contains('\n') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
 }
 
 /// Configuration for password generation
@@ -1,5 +1,13 @@
+use std::collections::HashMap;
+
 use sha1::{Digest, Sha1};
 
+use crate::errors::{PasswordManagerError, Result};
+
+/// Have I Been Pwned's k-anonymity range endpoint. Only the 5-char hash
+/// prefix is ever sent here.
+const HIBP_RANGE_URL: &str = "https://api.pwnedpasswords.com/range/";
+
 /// Check if a password has been breached (using k-anonymity)
 /// Returns (is_breached, count_in_breaches, hash_prefix)
 pub fn check_password_breach_local(password: &str) -> (bool, u32, String) {
@@ -21,6 +29,83 @@ pub fn check_password_breach_local(password: &str) -> (bool, u32, String) {
     (false, 0, prefix.to_string())
 }
 
+/// Check if a password has been breached via the real Have I Been Pwned
+/// range API, using the k-anonymity protocol: only the first 5 hex
+/// characters of the uppercase SHA-1 hash (the "range prefix") are ever
+/// transmitted. The response is every breached hash sharing that prefix, as
+/// `SUFFIX:COUNT` lines, and the remaining 35 characters (the "suffix") are
+/// matched against them locally — the suffix and full hash never leave this
+/// machine. A network failure is returned as an error rather than silently
+/// reported as "not breached", so callers can distinguish the two.
+/// Returns (is_breached, count_in_breaches, hash_prefix).
+pub fn check_password_breach_online(password: &str) -> Result<(bool, u32, String)> {
+    let hash_upper = hash_password_sha1(password).to_uppercase();
+    let prefix = &hash_upper[0..5];
+    let suffix = &hash_upper[5..];
+
+    let body = fetch_range_body(prefix)?;
+    let (is_breached, count) = find_suffix_count(&body, suffix);
+    Ok((is_breached, count, prefix.to_string()))
+}
+
+/// Fetches the raw `SUFFIX:COUNT` range response for one hash prefix. Only
+/// the prefix ever leaves this machine.
+fn fetch_range_body(prefix: &str) -> Result<String> {
+    let url = format!("{}{}", HIBP_RANGE_URL, prefix);
+    ureq::get(&url)
+        .call()
+        .map_err(|e| PasswordManagerError::NetworkError(e.to_string()))?
+        .into_string()
+        .map_err(|e| PasswordManagerError::NetworkError(e.to_string()))
+}
+
+/// Pluggable source for the HIBP k-anonymity range endpoint. Callers that
+/// want breach checking (e.g. `analyze_vault_health_with_breach_check`)
+/// take `&dyn BreachSource` instead of calling the network directly, so
+/// tests can swap in a `BreachSource` that returns canned responses.
+/// `range_for_prefix` receives only the 5-char hash prefix and returns the
+/// raw `SUFFIX:COUNT`-per-line response body for that prefix.
+pub trait BreachSource {
+    fn range_for_prefix(&self, prefix: &str) -> Result<String>;
+}
+
+/// Default `BreachSource`: the real HIBP range API.
+pub struct HibpBreachSource;
+
+impl BreachSource for HibpBreachSource {
+    fn range_for_prefix(&self, prefix: &str) -> Result<String> {
+        fetch_range_body(prefix)
+    }
+}
+
+/// Looks up one password's breach count via `source`. Only the first 5 hex
+/// characters of its uppercase SHA-1 hash are ever passed to `source`; the
+/// remaining 35 characters (the suffix) are matched against the returned
+/// range response locally, so the full hash never leaves this function.
+pub fn check_password_breach_with(source: &dyn BreachSource, password: &str) -> Result<u64> {
+    let hash_upper = hash_password_sha1(password).to_uppercase();
+    let prefix = &hash_upper[0..5];
+    let suffix = &hash_upper[5..];
+
+    let body = source.range_for_prefix(prefix)?;
+    let (_, count) = find_suffix_count(&body, suffix);
+    Ok(count as u64)
+}
+
+/// Scans a range response body for a line matching `suffix` (case-insensitive)
+/// and returns `(found, count)`.
+fn find_suffix_count(body: &str, suffix: &str) -> (bool, u32) {
+    for line in body.lines() {
+        let Some((line_suffix, count_str)) = line.split_once(':') else {
+            continue;
+        };
+        if line_suffix.eq_ignore_ascii_case(suffix) {
+            return (true, count_str.trim().parse().unwrap_or(0));
+        }
+    }
+    (false, 0)
+}
+
 /// Hash password with SHA-1 (used for Have I Been Pwned API)
 pub fn hash_password_sha1(password: &str) -> String {
     let mut hasher = Sha1::new();
@@ -97,10 +182,13 @@ pub struct BreachCheckResult {
     pub recommendation: String,
 }
 
-/// Comprehensive breach and weakness check
+/// Comprehensive breach and weakness check. Tries the real HIBP range API
+/// first and falls back to the local (offline) stub if the network call
+/// fails, so the common-password list still works without connectivity.
 pub fn check_password_security(password: &str) -> BreachCheckResult {
     let is_common = is_common_password(password);
-    let (is_breached, breach_count, hash_prefix) = check_password_breach_local(password);
+    let (is_breached, breach_count, hash_prefix) = check_password_breach_online(password)
+        .unwrap_or_else(|_| check_password_breach_local(password));
 
     let recommendation = if is_common {
         "This is a very common password. Change it immediately!".to_string()
@@ -124,14 +212,48 @@ pub fn check_password_security(password: &str) -> BreachCheckResult {
     }
 }
 
-/// Check multiple passwords for breaches (batch check)
-pub fn batch_check_passwords(passwords: &[String]) -> Vec<(String, bool, bool)> {
+/// Check multiple passwords for breaches, exploiting HIBP k-anonymity's
+/// structure: passwords are grouped by their shared 5-char hash prefix, and
+/// each distinct prefix costs exactly one range request no matter how many
+/// passwords share it, rather than one request per password. Falls back to
+/// the local (offline) check for any prefix whose request fails.
+/// Returns `(password, is_breached, breach_count, is_common)` per input,
+/// in the same order as `passwords`.
+pub fn batch_check_passwords(passwords: &[String]) -> Vec<(String, bool, u32, bool)> {
+    let hashes: Vec<String> = passwords
+        .iter()
+        .map(|p| hash_password_sha1(p).to_uppercase())
+        .collect();
+
+    let mut groups: HashMap<String, Vec<usize>> = HashMap::new();
+    for (i, hash) in hashes.iter().enumerate() {
+        groups.entry(hash[0..5].to_string()).or_default().push(i);
+    }
+
+    let mut breach_results: Vec<(bool, u32)> = vec![(false, 0); passwords.len()];
+    for (prefix, indices) in &groups {
+        match fetch_range_body(prefix) {
+            Ok(body) => {
+                for &i in indices {
+                    let suffix = &hashes[i][5..];
+                    breach_results[i] = find_suffix_count(&body, suffix);
+                }
+            }
+            Err(_) => {
+                for &i in indices {
+                    let (breached, count, _) = check_password_breach_local(&passwords[i]);
+                    breach_results[i] = (breached, count);
+                }
+            }
+        }
+    }
+
     passwords
         .iter()
-        .map(|p| {
-            let common = is_common_password(p);
-            let (breached, _, _) = check_password_breach_local(p);
-            (p.clone(), breached, common)
+        .enumerate()
+        .map(|(i, p)| {
+            let (is_breached, breach_count) = breach_results[i];
+            (p.clone(), is_breached, breach_count, is_common_password(p))
         })
         .collect()
 }
@@ -173,7 +295,34 @@ mod tests {
         let passwords = vec!["password".to_string(), "strongP@ssw0rd!123".to_string()];
         let results = batch_check_passwords(&passwords);
         assert_eq!(results.len(), 2);
-        assert!(results[0].2); // First password is common
-        assert!(!results[1].2); // Second password is not common
+        assert!(results[0].3); // First password is common
+        assert!(!results[1].3); // Second password is not common
+    }
+
+    struct MockBreachSource {
+        body: &'static str,
+    }
+
+    impl BreachSource for MockBreachSource {
+        fn range_for_prefix(&self, _prefix: &str) -> Result<String> {
+            Ok(self.body.to_string())
+        }
+    }
+
+    #[test]
+    fn test_check_password_breach_with_matches_suffix() {
+        // SHA-1("password") = 5BAA6|1E4C9B93F3F0682250B6CF8331B7EE68FD8
+        let source = MockBreachSource {
+            body: "1E4C9B93F3F0682250B6CF8331B7EE68FD8:3730471\r\nAAAA:1",
+        };
+        let count = check_password_breach_with(&source, "password").unwrap();
+        assert_eq!(count, 3730471);
+    }
+
+    #[test]
+    fn test_check_password_breach_with_no_match_returns_zero() {
+        let source = MockBreachSource { body: "AAAA:1" };
+        let count = check_password_breach_with(&source, "password").unwrap();
+        assert_eq!(count, 0);
     }
 }
@@ -1,3 +1,4 @@
+use zxcvbn::feedback::Suggestion;
 use zxcvbn::zxcvbn;
 
 /// Password strength score (0-4)
@@ -46,6 +47,53 @@ impl PasswordStrength {
     }
 }
 
+/// How long a password is estimated to survive a given attacker model, in
+/// both raw seconds (for comparisons/thresholds) and a human-readable
+/// rendering via `format_crack_time`.
+#[derive(Debug, Clone)]
+pub struct CrackTimeEstimate {
+    pub seconds: f64,
+    pub display: String,
+}
+
+impl CrackTimeEstimate {
+    fn from_guesses(guesses: f64, guesses_per_second: f64) -> Self {
+        let seconds = guesses / guesses_per_second;
+        Self {
+            display: format_crack_time(seconds),
+            seconds,
+        }
+    }
+}
+
+/// Estimated crack time under each of the four attacker models zxcvbn
+/// reasons about, all derived from the same total guess count so a
+/// password that looks safe online can still be flagged as falling in
+/// seconds against an offline, fast-hashing attacker.
+#[derive(Debug, Clone)]
+pub struct CrackTimeEstimates {
+    /// ~100 guesses/hour: an online attacker rate-limited by the service.
+    pub online_throttled: CrackTimeEstimate,
+    /// ~10 guesses/second: an online attacker with no rate limiting.
+    pub online_unthrottled: CrackTimeEstimate,
+    /// ~10,000 guesses/second: an offline attacker using a slow hash (e.g. bcrypt).
+    pub offline_slow_hashing: CrackTimeEstimate,
+    /// ~10,000,000,000 guesses/second: an offline attacker with GPUs/ASICs
+    /// against a fast, unsalted hash.
+    pub offline_fast_hashing: CrackTimeEstimate,
+}
+
+impl CrackTimeEstimates {
+    fn from_guesses(guesses: f64) -> Self {
+        Self {
+            online_throttled: CrackTimeEstimate::from_guesses(guesses, 100.0 / 3600.0),
+            online_unthrottled: CrackTimeEstimate::from_guesses(guesses, 10.0),
+            offline_slow_hashing: CrackTimeEstimate::from_guesses(guesses, 1e4),
+            offline_fast_hashing: CrackTimeEstimate::from_guesses(guesses, 1e10),
+        }
+    }
+}
+
 /// Password strength analysis result
 #[derive(Debug, Clone)]
 pub struct PasswordAnalysis {
@@ -54,6 +102,7 @@ pub struct PasswordAnalysis {
     pub entropy: f64,
     pub crack_time_seconds: Option<f64>,
     pub crack_time_display: String,
+    pub crack_times: CrackTimeEstimates,
     pub warning: Option<String>,
     pub suggestions: Vec<String>,
 }
@@ -65,12 +114,14 @@ pub fn analyze_password(password: &str, user_inputs: &[&str]) -> PasswordAnalysi
     let strength = PasswordStrength::from_score(result.score() as u8);
     let entropy = result.guesses_log10() * std::f64::consts::LOG2_10; // Convert log10 to bits
 
-    // Format crack time - zxcvbn 3.x has a different API
-    let crack_time_display = format!(
-        "{}",
-        result.crack_times().offline_slow_hashing_1e4_per_second()
-    );
-    let crack_time_seconds = None; // zxcvbn 3.x doesn't expose raw seconds easily
+    // zxcvbn's `crack_times()` returns Decimal-backed estimates per
+    // attacker model; rather than depend on that type's conversions, derive
+    // every scenario from the same underlying guess count ourselves so the
+    // rates used here (throttled/unthrottled/slow/fast) are explicit.
+    let guesses = result.guesses() as f64;
+    let crack_times = CrackTimeEstimates::from_guesses(guesses);
+    let crack_time_seconds = Some(crack_times.offline_slow_hashing.seconds);
+    let crack_time_display = crack_times.offline_slow_hashing.display.clone();
 
     // Get feedback
     let feedback_opt = result.feedback();
@@ -79,7 +130,7 @@ pub fn analyze_password(password: &str, user_inputs: &[&str]) -> PasswordAnalysi
         .map(|w| w.to_string());
 
     let suggestions: Vec<String> = feedback_opt
-        .map(|f| f.suggestions().iter().map(|s| format!("{:?}", s)).collect())
+        .map(|f| f.suggestions().iter().map(suggestion_text).collect())
         .unwrap_or_default();
 
     PasswordAnalysis {
@@ -88,11 +139,57 @@ pub fn analyze_password(password: &str, user_inputs: &[&str]) -> PasswordAnalysi
         entropy,
         crack_time_seconds,
         crack_time_display,
+        crack_times,
         warning,
         suggestions,
     }
 }
 
+/// Renders one of zxcvbn's suggestion variants as the human-readable advice
+/// it represents, matching the wording zxcvbn's own feedback uses upstream.
+/// Falls back to a generic message for any variant not covered here, so a
+/// future zxcvbn upgrade that adds variants degrades gracefully instead of
+/// failing to compile.
+fn suggestion_text(suggestion: &Suggestion) -> String {
+    match suggestion {
+        Suggestion::AddAnotherWordOrTwo => {
+            "Add another word or two. Uncommon words are better.".to_string()
+        }
+        Suggestion::CapitalizationDoesntHelp => {
+            "Capitalization doesn't help very much.".to_string()
+        }
+        Suggestion::AllUppercaseEasy => {
+            "All-uppercase is almost as easy to guess as all-lowercase.".to_string()
+        }
+        Suggestion::ReversedWordEasy => {
+            "Reversed words aren't much harder to guess.".to_string()
+        }
+        Suggestion::PredictableSubstitutionsEasy => {
+            "Predictable substitutions like '@' for 'a' don't help much.".to_string()
+        }
+        Suggestion::UseAFewWordsAvoidCommonPhrases => {
+            "Use a few words, avoid common phrases.".to_string()
+        }
+        Suggestion::NoNeedForSymbolsDigitsOrUppercaseLetters => {
+            "No need for symbols, digits, or uppercase letters.".to_string()
+        }
+        Suggestion::AvoidRepeatedWordsAndCharacters => {
+            "Avoid repeated words and characters.".to_string()
+        }
+        Suggestion::AvoidSequences => "Avoid sequences like 'abc' or '6543'.".to_string(),
+        Suggestion::AvoidRecentYears => {
+            "Avoid recent years and years that are associated with you.".to_string()
+        }
+        Suggestion::AvoidYearsAssociatedWithYou => {
+            "Avoid years associated with you.".to_string()
+        }
+        Suggestion::AvoidDatesAndYearsAssociatedWithYou => {
+            "Avoid dates and years that are associated with you.".to_string()
+        }
+        other => format!("{:?}", other),
+    }
+}
+
 /// Format crack time in human-readable format
 fn format_crack_time(seconds: f64) -> String {
     if seconds < 1.0 {
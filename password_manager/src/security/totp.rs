@@ -1,20 +1,92 @@
 use rand::Rng;
-use totp_lite::{totp_custom, Sha1, DEFAULT_STEP};
+use totp_lite::{hotp_custom, Sha1, Sha256, Sha512, DEFAULT_STEP};
+
+use crate::locked_bytes::LockedBytes;
+
+/// Which HMAC hash a TOTP/HOTP code is computed with. Most services default
+/// to SHA-1, but the otpauth spec also allows SHA-256 and SHA-512.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TotpAlgorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl Default for TotpAlgorithm {
+    fn default() -> Self {
+        TotpAlgorithm::Sha1
+    }
+}
+
+/// Whether a credential's one-time code is time-based (TOTP) or
+/// counter-based (HOTP). `Hotp`'s counter is carried in this value (it is
+/// not stored on `Credential`) and must be incremented by the caller each
+/// time a code is generated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OtpMode {
+    Totp { period: u64 },
+    Hotp { counter: u64 },
+}
+
+impl Default for OtpMode {
+    fn default() -> Self {
+        OtpMode::Totp { period: DEFAULT_STEP }
+    }
+}
+
+/// Algorithm, digit count, and period/counter mode for a TOTP/HOTP secret.
+/// `generate_totp`/`verify_totp` use `TotpConfig::default()` (SHA-1, 6
+/// digits, 30s period) to stay backward compatible with vaults that predate
+/// this struct.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TotpConfig {
+    pub algorithm: TotpAlgorithm,
+    pub digits: u32,
+    pub mode: OtpMode,
+}
+
+impl Default for TotpConfig {
+    fn default() -> Self {
+        TotpConfig {
+            algorithm: TotpAlgorithm::default(),
+            digits: 6,
+            mode: OtpMode::default(),
+        }
+    }
+}
 
 /// Generate a TOTP code from a secret
 pub fn generate_totp(secret: &str) -> Result<String, String> {
-    // Decode base32 secret
-    let secret_bytes = decode_base32(secret)?;
-
-    // Generate TOTP code
     let timestamp = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .map_err(|e| format!("Failed to get system time: {}", e))?
         .as_secs();
 
-    let code = totp_custom::<Sha1>(DEFAULT_STEP, 6, &secret_bytes, timestamp);
+    generate_totp_with(secret, &TotpConfig::default(), timestamp)
+}
+
+/// Generate a TOTP/HOTP code from a secret under a given `TotpConfig`.
+/// `timestamp` is the current Unix time for `OtpMode::Totp`, and is ignored
+/// in favor of `counter` for `OtpMode::Hotp`.
+pub fn generate_totp_with(
+    secret: &str,
+    config: &TotpConfig,
+    timestamp: u64,
+) -> Result<String, String> {
+    let secret_bytes = decode_base32(secret)?;
+
+    let counter = match config.mode {
+        OtpMode::Totp { period } => timestamp / period.max(1),
+        OtpMode::Hotp { counter } => counter,
+    };
+
+    let code = match config.algorithm {
+        TotpAlgorithm::Sha1 => hotp_custom::<Sha1>(config.digits, &secret_bytes, counter),
+        TotpAlgorithm::Sha256 => hotp_custom::<Sha256>(config.digits, &secret_bytes, counter),
+        TotpAlgorithm::Sha512 => hotp_custom::<Sha512>(config.digits, &secret_bytes, counter),
+    };
 
-    Ok(format!("{:06}", code))
+    Ok(format!("{:0width$}", code, width = config.digits as usize))
 }
 
 /// Generate a random TOTP secret (base32 encoded)
@@ -24,14 +96,83 @@ pub fn generate_totp_secret() -> String {
     encode_base32(&secret)
 }
 
-/// Verify a TOTP code against a secret
+/// Default number of steps on either side of "now" that `verify_totp`
+/// tolerates, to absorb clock drift between the user's device and ours.
+const DEFAULT_SKEW_STEPS: i64 = 1;
+
+/// Verify a TOTP code against a secret, tolerating `DEFAULT_SKEW_STEPS` of
+/// clock drift either way.
 pub fn verify_totp(secret: &str, code: &str) -> Result<bool, String> {
-    let expected = generate_totp(secret)?;
-    Ok(expected == code)
+    verify_totp_window(secret, code, DEFAULT_SKEW_STEPS)
+}
+
+/// Verify a TOTP code against a secret, accepting it if it matches any step
+/// within `skew_steps` of the current one (e.g. `skew_steps = 1` checks the
+/// previous, current, and next step). Uses the default `TotpConfig`.
+pub fn verify_totp_window(secret: &str, code: &str, skew_steps: i64) -> Result<bool, String> {
+    verify_totp_window_with(secret, code, skew_steps, &TotpConfig::default())
+}
+
+/// Verify a TOTP code under a given `TotpConfig`, accepting it if it
+/// matches any step within `skew_steps` of the current one. Compares each
+/// candidate using a constant-time byte comparison so a timing side channel
+/// can't reveal how many leading digits of `code` were correct.
+///
+/// For `OtpMode::Hotp`, there is no "current step" to drift from, so
+/// `skew_steps` is ignored and only the stored counter's code is checked.
+pub fn verify_totp_window_with(
+    secret: &str,
+    code: &str,
+    skew_steps: i64,
+    config: &TotpConfig,
+) -> Result<bool, String> {
+    let period = match config.mode {
+        OtpMode::Totp { period } => period,
+        OtpMode::Hotp { .. } => {
+            let expected = generate_totp_with(secret, config, 0)?;
+            return Ok(constant_time_eq(expected.as_bytes(), code.as_bytes()));
+        }
+    };
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_err(|e| format!("Failed to get system time: {}", e))?
+        .as_secs();
+    let current_step = (now / period.max(1)) as i64;
+
+    for offset in -skew_steps..=skew_steps {
+        let step = current_step + offset;
+        if step < 0 {
+            continue;
+        }
+        let timestamp = step as u64 * period;
+        let expected = generate_totp_with(secret, config, timestamp)?;
+        if constant_time_eq(expected.as_bytes(), code.as_bytes()) {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
 }
 
-/// Decode base32 string to bytes
-fn decode_base32(input: &str) -> Result<Vec<u8>, String> {
+/// Compares two byte strings in constant time with respect to their
+/// contents (the loop always runs over the full length of `a`), so a
+/// verification failure doesn't leak how many leading bytes matched. Still
+/// short-circuits on length, since code lengths aren't secret.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut diff = 0u8;
+    for (x, y) in a.iter().zip(b.iter()) {
+        diff |= x ^ y;
+    }
+    diff == 0
+}
+
+/// Decode a base32 secret to raw bytes, locked in RAM and zeroized on drop
+/// (see `LockedBytes`) since this is the decoded TOTP/HOTP seed itself.
+fn decode_base32(input: &str) -> Result<LockedBytes, String> {
     const BASE32_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
 
     let input = input.to_uppercase().replace(['=', ' ', '-'], "");
@@ -55,7 +196,7 @@ fn decode_base32(input: &str) -> Result<Vec<u8>, String> {
         }
     }
 
-    Ok(output)
+    LockedBytes::new(output)
 }
 
 /// Encode bytes to base32 string
@@ -100,17 +241,147 @@ pub fn format_totp_code(code: &str) -> String {
     }
 }
 
-/// Generate a TOTP URI for QR code generation
+/// Generate a TOTP URI for QR code generation, using the default
+/// `TotpConfig` (SHA-1, 6 digits, 30s period).
 pub fn generate_totp_uri(secret: &str, account: &str, issuer: &str) -> String {
+    generate_totp_uri_with(secret, account, issuer, &TotpConfig::default())
+}
+
+/// Generate an otpauth:// URI for QR code generation, including the
+/// `algorithm`, `digits`, and `period` parameters for `config`. HOTP
+/// credentials aren't represented by this URI form, so `config.mode` must
+/// be `OtpMode::Totp`.
+pub fn generate_totp_uri_with(
+    secret: &str,
+    account: &str,
+    issuer: &str,
+    config: &TotpConfig,
+) -> String {
+    let period = match config.mode {
+        OtpMode::Totp { period } => period,
+        OtpMode::Hotp { .. } => DEFAULT_STEP,
+    };
+    let algorithm = match config.algorithm {
+        TotpAlgorithm::Sha1 => "SHA1",
+        TotpAlgorithm::Sha256 => "SHA256",
+        TotpAlgorithm::Sha512 => "SHA512",
+    };
+
     format!(
-        "otpauth://totp/{}:{}?secret={}&issuer={}",
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm={}&digits={}&period={}",
         urlencoding::encode(issuer),
         urlencoding::encode(account),
         secret,
-        urlencoding::encode(issuer)
+        urlencoding::encode(issuer),
+        algorithm,
+        config.digits,
+        period
     )
 }
 
+/// A TOTP/HOTP secret parsed from an `otpauth://` URI, as emitted by an
+/// authenticator app's QR export or by `generate_totp_uri`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TotpEntry {
+    pub issuer: String,
+    pub account: String,
+    pub secret: String,
+    pub config: TotpConfig,
+}
+
+/// Parses an `otpauth://totp/...` or `otpauth://hotp/...` URI — as printed
+/// by `generate_totp_uri`, or scanned from another authenticator's QR code
+/// — into a `TotpEntry`. The secret is validated against the base32
+/// alphabet `decode_base32` uses, but stored back as the original base32
+/// string, the same as everywhere else in this module.
+pub fn parse_totp_uri(uri: &str) -> Result<TotpEntry, String> {
+    let rest = uri
+        .strip_prefix("otpauth://")
+        .ok_or_else(|| "not an otpauth:// URI".to_string())?;
+
+    let (otp_type, rest) = rest
+        .split_once('/')
+        .ok_or_else(|| "missing otpauth type".to_string())?;
+
+    let is_hotp = match otp_type {
+        "totp" => false,
+        "hotp" => true,
+        other => return Err(format!("unsupported otpauth type: {other}")),
+    };
+
+    let (label, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let label = urlencoding::decode(label)
+        .map_err(|e| format!("invalid label encoding: {e}"))?
+        .into_owned();
+
+    let (label_issuer, account) = match label.split_once(':') {
+        Some((issuer, account)) => (Some(issuer.to_string()), account.to_string()),
+        None => (None, label),
+    };
+
+    let mut params: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = pair
+            .split_once('=')
+            .ok_or_else(|| format!("malformed query parameter: {pair}"))?;
+        let value = urlencoding::decode(value)
+            .map_err(|e| format!("invalid query parameter encoding: {e}"))?
+            .into_owned();
+        params.insert(key.to_string(), value);
+    }
+
+    let secret = params
+        .get("secret")
+        .ok_or_else(|| "missing secret parameter".to_string())?
+        .clone();
+    // Validate against the base32 alphabet decode_base32 uses without
+    // holding onto the decoded bytes past this check.
+    decode_base32(&secret)?;
+
+    let issuer = params.get("issuer").cloned().or(label_issuer).unwrap_or_default();
+
+    let algorithm = match params.get("algorithm").map(|a| a.to_uppercase()) {
+        Some(ref a) if a == "SHA256" => TotpAlgorithm::Sha256,
+        Some(ref a) if a == "SHA512" => TotpAlgorithm::Sha512,
+        _ => TotpAlgorithm::Sha1,
+    };
+
+    let digits = params
+        .get("digits")
+        .map(|d| d.parse::<u32>())
+        .transpose()
+        .map_err(|_| "invalid digits parameter".to_string())?
+        .unwrap_or(6);
+
+    let mode = if is_hotp {
+        let counter = params
+            .get("counter")
+            .ok_or_else(|| "hotp URI missing counter parameter".to_string())?
+            .parse::<u64>()
+            .map_err(|_| "invalid counter parameter".to_string())?;
+        OtpMode::Hotp { counter }
+    } else {
+        let period = params
+            .get("period")
+            .map(|p| p.parse::<u64>())
+            .transpose()
+            .map_err(|_| "invalid period parameter".to_string())?
+            .unwrap_or(DEFAULT_STEP);
+        OtpMode::Totp { period }
+    };
+
+    Ok(TotpEntry {
+        issuer,
+        account,
+        secret,
+        config: TotpConfig {
+            algorithm,
+            digits,
+            mode,
+        },
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -120,7 +391,7 @@ mod tests {
         let original = b"Hello World";
         let encoded = encode_base32(original);
         let decoded = decode_base32(&encoded).unwrap();
-        assert_eq!(original.to_vec(), decoded);
+        assert_eq!(original.to_vec(), decoded.to_vec());
     }
 
     #[test]
@@ -158,5 +429,159 @@ mod tests {
         let uri = generate_totp_uri("SECRET", "user@example.com", "MyApp");
         assert!(uri.starts_with("otpauth://totp/"));
         assert!(uri.contains("secret=SECRET"));
+        assert!(uri.contains("algorithm=SHA1"));
+        assert!(uri.contains("digits=6"));
+        assert!(uri.contains("period=30"));
+    }
+
+    #[test]
+    fn test_totp_uri_with_custom_config() {
+        let config = TotpConfig {
+            algorithm: TotpAlgorithm::Sha512,
+            digits: 8,
+            mode: OtpMode::Totp { period: 60 },
+        };
+        let uri = generate_totp_uri_with("SECRET", "user@example.com", "MyApp", &config);
+        assert!(uri.contains("algorithm=SHA512"));
+        assert!(uri.contains("digits=8"));
+        assert!(uri.contains("period=60"));
+    }
+
+    #[test]
+    fn test_parse_totp_uri_round_trips_generate_totp_uri() {
+        let config = TotpConfig {
+            algorithm: TotpAlgorithm::Sha256,
+            digits: 8,
+            mode: OtpMode::Totp { period: 60 },
+        };
+        let uri = generate_totp_uri_with("JBSWY3DPEHPK3PXP", "user@example.com", "MyApp", &config);
+
+        let entry = parse_totp_uri(&uri).unwrap();
+        assert_eq!(entry.issuer, "MyApp");
+        assert_eq!(entry.account, "user@example.com");
+        assert_eq!(entry.secret, "JBSWY3DPEHPK3PXP");
+        assert_eq!(entry.config, config);
+    }
+
+    #[test]
+    fn test_parse_totp_uri_defaults_without_optional_params() {
+        let entry = parse_totp_uri("otpauth://totp/MyApp:user@example.com?secret=JBSWY3DPEHPK3PXP")
+            .unwrap();
+        assert_eq!(entry.issuer, "MyApp");
+        assert_eq!(entry.account, "user@example.com");
+        assert_eq!(entry.config, TotpConfig::default());
+    }
+
+    #[test]
+    fn test_parse_totp_uri_hotp_requires_counter() {
+        let err = parse_totp_uri("otpauth://hotp/MyApp:user@example.com?secret=JBSWY3DPEHPK3PXP")
+            .unwrap_err();
+        assert!(err.contains("counter"));
+
+        let entry =
+            parse_totp_uri("otpauth://hotp/MyApp:user@example.com?secret=JBSWY3DPEHPK3PXP&counter=5")
+                .unwrap();
+        assert_eq!(entry.config.mode, OtpMode::Hotp { counter: 5 });
+    }
+
+    #[test]
+    fn test_parse_totp_uri_rejects_invalid_secret() {
+        let err = parse_totp_uri("otpauth://totp/MyApp:user@example.com?secret=not-valid-base32!")
+            .unwrap_err();
+        assert!(err.contains("Invalid base32"));
+    }
+
+    #[test]
+    fn test_parse_totp_uri_rejects_non_otpauth_scheme() {
+        assert!(parse_totp_uri("https://example.com").is_err());
+    }
+
+    #[test]
+    fn test_generate_totp_with_matches_default_generate_totp() {
+        let secret = generate_totp_secret();
+        let timestamp = 1_700_000_000u64;
+        let via_default = generate_totp_with(&secret, &TotpConfig::default(), timestamp).unwrap();
+        let counter_config = TotpConfig {
+            algorithm: TotpAlgorithm::Sha1,
+            digits: 6,
+            mode: OtpMode::Totp { period: DEFAULT_STEP },
+        };
+        let via_explicit = generate_totp_with(&secret, &counter_config, timestamp).unwrap();
+        assert_eq!(via_default, via_explicit);
+    }
+
+    #[test]
+    fn test_hotp_counter_mode() {
+        let secret = generate_totp_secret();
+        let config = TotpConfig {
+            algorithm: TotpAlgorithm::Sha1,
+            digits: 6,
+            mode: OtpMode::Hotp { counter: 0 },
+        };
+        let code_at_0 = generate_totp_with(&secret, &config, 0).unwrap();
+
+        let config_next = TotpConfig {
+            mode: OtpMode::Hotp { counter: 1 },
+            ..config
+        };
+        let code_at_1 = generate_totp_with(&secret, &config_next, 0).unwrap();
+
+        assert_eq!(code_at_0.len(), 6);
+        assert_ne!(code_at_0, code_at_1);
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"123456", b"123456"));
+        assert!(!constant_time_eq(b"123456", b"654321"));
+        assert!(!constant_time_eq(b"123456", b"12345"));
+    }
+
+    #[test]
+    fn test_verify_totp_window_tolerates_clock_drift() {
+        let secret = generate_totp_secret();
+        let config = TotpConfig::default();
+        let period = 30u64;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let previous_step_timestamp = now.saturating_sub(period);
+        let code_from_previous_step =
+            generate_totp_with(&secret, &config, previous_step_timestamp).unwrap();
+
+        assert!(verify_totp_window(&secret, &code_from_previous_step, 1).unwrap());
+        assert!(!verify_totp_window(&secret, &code_from_previous_step, 0).unwrap());
+    }
+
+    #[test]
+    fn test_verify_totp_window_rejects_wrong_code() {
+        let secret = generate_totp_secret();
+        assert!(!verify_totp_window(&secret, "000000", 1).unwrap());
+    }
+
+    #[test]
+    fn test_verify_totp_window_with_hotp_ignores_skew() {
+        let secret = generate_totp_secret();
+        let config = TotpConfig {
+            algorithm: TotpAlgorithm::Sha1,
+            digits: 6,
+            mode: OtpMode::Hotp { counter: 5 },
+        };
+        let code = generate_totp_with(&secret, &config, 0).unwrap();
+        assert!(verify_totp_window_with(&secret, &code, 1, &config).unwrap());
+        assert!(!verify_totp_window_with(&secret, "000000", 1, &config).unwrap());
+    }
+
+    #[test]
+    fn test_eight_digit_codes() {
+        let secret = generate_totp_secret();
+        let config = TotpConfig {
+            algorithm: TotpAlgorithm::Sha256,
+            digits: 8,
+            mode: OtpMode::Totp { period: 30 },
+        };
+        let code = generate_totp_with(&secret, &config, 1_700_000_000).unwrap();
+        assert_eq!(code.len(), 8);
     }
 }
@@ -0,0 +1,181 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+use crate::errors::{PasswordManagerError, Result};
+
+/// Where the encrypted vault blob lives and how to get it in and out.
+/// Crypto and credential logic never touch `VaultStorage` directly — they
+/// only ever see the encrypted bytes `VaultManager` reads from and writes
+/// through it, so the same blob can live on local disk, in an S3-compatible
+/// bucket, or anywhere else a backend is written for.
+pub trait VaultStorage: std::fmt::Debug {
+    /// Read the full encrypted vault blob.
+    fn read(&self) -> Result<Vec<u8>>;
+
+    /// Overwrite the encrypted vault blob.
+    fn write(&self, data: &[u8]) -> Result<()>;
+
+    /// Whether a vault blob currently exists at this location.
+    fn exists(&self) -> bool;
+
+    /// Delete the vault blob.
+    fn delete(&self) -> Result<()>;
+
+    /// Human-readable location, used in error messages (e.g.
+    /// `PasswordManagerError::VaultAlreadyExists`).
+    fn description(&self) -> String;
+
+    /// Restore the previous version of the blob, if this backend keeps
+    /// one. Backends without a backup (e.g. `S3Storage`) return an error.
+    fn rollback(&self) -> Result<()> {
+        Err(PasswordManagerError::InvalidInput(
+            "This storage backend does not support rollback".to_string(),
+        ))
+    }
+}
+
+/// Default backend: the encrypted vault blob as a single file on local disk.
+#[derive(Debug, Clone)]
+pub struct FileStorage {
+    path: PathBuf,
+}
+
+impl FileStorage {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn tmp_path(&self) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(".tmp");
+        self.path.with_file_name(name)
+    }
+
+    fn backup_path(&self) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(".bak");
+        self.path.with_file_name(name)
+    }
+}
+
+impl VaultStorage for FileStorage {
+    fn read(&self) -> Result<Vec<u8>> {
+        Ok(fs::read(&self.path)?)
+    }
+
+    /// Writes `data` to a sibling temp file in the same directory, `fsync`s
+    /// it, copies whatever is currently at `path` to a `.bak` sibling (so
+    /// `rollback` has something to restore), then renames the temp file
+    /// over the real path. Rename is atomic on the same filesystem, so a
+    /// crash or power loss mid-write leaves either the old vault or the new
+    /// one intact — never a truncated file.
+    fn write(&self, data: &[u8]) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let tmp_path = self.tmp_path();
+        let mut tmp_file = fs::File::create(&tmp_path)?;
+        tmp_file.write_all(data)?;
+        tmp_file.sync_all()?;
+        drop(tmp_file);
+
+        if self.path.exists() {
+            fs::copy(&self.path, self.backup_path())?;
+        }
+
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+
+    fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    fn delete(&self) -> Result<()> {
+        fs::remove_file(&self.path)?;
+        Ok(())
+    }
+
+    fn description(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    fn rollback(&self) -> Result<()> {
+        let backup = self.backup_path();
+        if !backup.exists() {
+            return Err(PasswordManagerError::InvalidInput(
+                "No backup available to roll back to".to_string(),
+            ));
+        }
+        fs::copy(&backup, &self.path)?;
+        Ok(())
+    }
+}
+
+/// Remote backend for S3-compatible object stores (AWS S3, MinIO, etc.),
+/// addressed via a pre-signed or otherwise pre-authorized HTTP endpoint.
+/// This deliberately doesn't implement SigV4 request signing itself — it
+/// expects `endpoint` to already be a URL the caller is authorized to
+/// PUT/GET/HEAD/DELETE against (e.g. a pre-signed URL, or a bucket behind an
+/// authenticating reverse proxy), with an optional bearer token for the
+/// latter case. That keeps this backend a thin HTTP client, consistent with
+/// how `security::breach` already talks to the HIBP API.
+#[derive(Debug, Clone)]
+pub struct S3Storage {
+    object_url: String,
+    auth_token: Option<String>,
+}
+
+impl S3Storage {
+    pub fn new(object_url: impl Into<String>, auth_token: Option<String>) -> Self {
+        Self {
+            object_url: object_url.into(),
+            auth_token,
+        }
+    }
+
+    fn request(&self, method: &str) -> ureq::Request {
+        let req = ureq::request(method, &self.object_url);
+        match &self.auth_token {
+            Some(token) => req.set("Authorization", &format!("Bearer {}", token)),
+            None => req,
+        }
+    }
+}
+
+impl VaultStorage for S3Storage {
+    fn read(&self) -> Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        self.request("GET")
+            .call()
+            .map_err(|e| PasswordManagerError::NetworkError(e.to_string()))?
+            .into_reader()
+            .read_to_end(&mut buf)
+            .map_err(|e| PasswordManagerError::NetworkError(e.to_string()))?;
+        Ok(buf)
+    }
+
+    fn write(&self, data: &[u8]) -> Result<()> {
+        self.request("PUT")
+            .send_bytes(data)
+            .map_err(|e| PasswordManagerError::NetworkError(e.to_string()))?;
+        Ok(())
+    }
+
+    fn exists(&self) -> bool {
+        self.request("HEAD").call().is_ok()
+    }
+
+    fn delete(&self) -> Result<()> {
+        self.request("DELETE")
+            .call()
+            .map_err(|e| PasswordManagerError::NetworkError(e.to_string()))?;
+        Ok(())
+    }
+
+    fn description(&self) -> String {
+        self.object_url.clone()
+    }
+}
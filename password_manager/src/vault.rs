@@ -1,28 +1,74 @@
 use std::fs;
-use std::path::PathBuf;
-
-use zeroize::Zeroizing;
-
-use crate::crypto::{decrypt, derive_key, encrypt, generate_salt};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::concurrent::ConcurrentVault;
+use crate::crypto::{
+    calibrate_kdf_params, decrypt, derive_key_with_params, encrypt, generate_salt,
+    CipherAlgorithm, KdfParams, CALIBRATION_MEMORY_CEILING_KIB, CALIBRATION_TARGET,
+};
 use crate::errors::{PasswordManagerError, Result};
-use crate::models::{Credential, EncryptedVault, Vault};
+use crate::format::{bitwarden_json_to_credentials, credentials_to_bitwarden_json, Format};
+use crate::hasher::Hasher;
+use crate::locked_bytes::LockedBytes;
+use crate::models::{AuditLogEntry, Credential, EncryptedVault, Vault, VaultSnapshot};
+use crate::security::batch_check_passwords;
+use crate::storage::{FileStorage, VaultStorage};
 
 const VAULT_VERSION: u32 = 1;
 
-/// VaultManager handles all vault operations
-pub struct VaultManager {
-    vault_path: PathBuf,
-    vault: Option<Vault>,
-    master_key: Option<Zeroizing<[u8; 32]>>,
+/// Per-service result of `VaultManager::audit_all()`.
+#[derive(Debug, Clone)]
+pub struct AuditEntry {
+    pub service: String,
+    pub is_breached: bool,
+    pub breach_count: u32,
+    pub is_common: bool,
+}
+
+/// Marker state: no master key is held and no vault is decrypted in memory.
+/// `VaultManager<Locked>` only exposes operations that don't need the
+/// decrypted vault.
+pub struct Locked;
+
+/// Marker state: the vault is decrypted and the master key is held in
+/// memory. Only `VaultManager<Unlocked>` exposes credential operations, so
+/// calling them on a locked manager is a compile error instead of the
+/// `InvalidInput("Vault is locked")` runtime error this used to be.
+pub struct Unlocked {
+    vault: Vault,
+    master_key: LockedBytes,
+    salt: Vec<u8>,
+    algorithm: CipherAlgorithm,
+    kdf_params: KdfParams,
 }
 
-impl VaultManager {
-    /// Create a new VaultManager with the specified vault path
+/// VaultManager handles all vault operations. The encrypted vault blob is
+/// read and written through a `VaultStorage` backend, so the same crypto
+/// and credential logic works whether the blob lives on local disk or in a
+/// remote object store. Its lock state is part of the type (`Locked` or
+/// `Unlocked`): `initialize`/`unlock` consume a `VaultManager<Locked>` and
+/// return a `VaultManager<Unlocked>`, and `lock` does the reverse.
+pub struct VaultManager<State = Locked> {
+    storage: Box<dyn VaultStorage>,
+    state: State,
+}
+
+impl VaultManager<Locked> {
+    /// Create a new, locked VaultManager backed by a local file at
+    /// `vault_path`.
     pub fn new(vault_path: PathBuf) -> Self {
+        Self::with_storage(Box::new(FileStorage::new(vault_path)))
+    }
+
+    /// Create a new, locked VaultManager backed by an arbitrary
+    /// `VaultStorage` implementation (e.g. `FileStorage` or an
+    /// S3-compatible backend).
+    pub fn with_storage(storage: Box<dyn VaultStorage>) -> Self {
         Self {
-            vault_path,
-            vault: None,
-            master_key: None,
+            storage,
+            state: Locked,
         }
     }
 
@@ -41,210 +87,473 @@ impl VaultManager {
 
     /// Check if vault exists
     pub fn vault_exists(&self) -> bool {
-        self.vault_path.exists()
+        self.storage.exists()
+    }
+
+    /// Restores the vault from the storage backend's rolling backup (e.g.
+    /// `vault.enc.bak` for `FileStorage`), undoing the most recent save.
+    pub fn rollback(&self) -> Result<()> {
+        self.storage.rollback()
     }
 
-    /// Initialize a new vault with a master password
-    pub fn initialize(&mut self, master_password: &str) -> Result<()> {
+    /// Initialize a new vault with a master password, consuming the locked
+    /// manager and returning it unlocked.
+    pub fn initialize(self, master_password: &str) -> Result<VaultManager<Unlocked>> {
         if self.vault_exists() {
             return Err(PasswordManagerError::VaultAlreadyExists(
-                self.vault_path.display().to_string(),
+                self.storage.description(),
             ));
         }
 
-        // Create parent directories if they don't exist
-        if let Some(parent) = self.vault_path.parent() {
-            fs::create_dir_all(parent)?;
-        }
-
-        // Generate salt for key derivation
         let salt = generate_salt();
-
-        // Derive encryption key from master password
-        let key = derive_key(master_password, &salt)?;
-
-        // Create empty vault
+        let kdf_params = calibrate_kdf_params(CALIBRATION_TARGET, CALIBRATION_MEMORY_CEILING_KIB);
+        let master_key = derive_key_with_params(master_password, &salt, kdf_params)?;
         let vault = Vault::new();
 
-        // Save the vault
-        self.master_key = Some(key);
-        self.vault = Some(vault);
-        self.save(&salt)?;
-
-        Ok(())
+        // New vaults use XChaCha20-Poly1305: its 192-bit random nonce has no
+        // practical birthday-bound reuse risk, which matters since a vault
+        // gets re-encrypted on every save over its lifetime. Vaults written
+        // before this field existed stay on AES-256-GCM via their persisted
+        // `algorithm` tag.
+        let mut manager = VaultManager {
+            storage: self.storage,
+            state: Unlocked {
+                vault,
+                master_key,
+                salt: salt.clone(),
+                algorithm: CipherAlgorithm::XChaCha20Poly1305,
+                kdf_params,
+            },
+        };
+        manager.save()?;
+        Ok(manager)
     }
 
-    /// Unlock the vault with master password
-    pub fn unlock(&mut self, master_password: &str) -> Result<()> {
+    /// Unlock the vault with the master password, consuming the locked
+    /// manager and returning it unlocked.
+    pub fn unlock(self, master_password: &str) -> Result<VaultManager<Unlocked>> {
         if !self.vault_exists() {
             return Err(PasswordManagerError::VaultNotFound);
         }
 
-        // Read encrypted vault from disk
-        let encrypted_data = fs::read(&self.vault_path)?;
+        let encrypted_data = self.storage.read()?;
         let encrypted_vault: EncryptedVault = serde_json::from_slice(&encrypted_data)?;
 
-        // Derive key from master password and stored salt
-        let key = derive_key(master_password, &encrypted_vault.salt)?;
-
-        // Decrypt vault data
-        let decrypted_data = decrypt(&encrypted_vault.ciphertext, &key, &encrypted_vault.nonce)?;
+        let master_key = derive_key_with_params(
+            master_password,
+            &encrypted_vault.salt,
+            encrypted_vault.kdf_params,
+        )?;
+        let decrypted_data = decrypt(
+            &encrypted_vault.ciphertext,
+            &master_key,
+            &encrypted_vault.nonce,
+            encrypted_vault.algorithm,
+        )?;
 
-        // Deserialize vault
         let vault: Vault = serde_json::from_slice(&decrypted_data)
             .map_err(|_| PasswordManagerError::InvalidMasterPassword)?;
 
-        self.vault = Some(vault);
-        self.master_key = Some(key);
-
-        Ok(())
+        Ok(VaultManager {
+            storage: self.storage,
+            state: Unlocked {
+                vault,
+                master_key,
+                salt: encrypted_vault.salt,
+                algorithm: encrypted_vault.algorithm,
+                kdf_params: encrypted_vault.kdf_params,
+            },
+        })
     }
+}
 
-    /// Save the vault to disk (encrypted)
-    fn save(&self, salt: &[u8]) -> Result<()> {
-        let vault = self
-            .vault
-            .as_ref()
-            .ok_or(PasswordManagerError::InvalidInput(
-                "Vault not loaded".to_string(),
-            ))?;
-
-        let key = self
-            .master_key
-            .as_ref()
-            .ok_or(PasswordManagerError::InvalidInput(
-                "Master key not set".to_string(),
-            ))?;
-
-        // Serialize vault
-        let vault_json = serde_json::to_vec(vault)?;
+impl VaultManager<Unlocked> {
+    /// Save the vault to storage (encrypted). Refreshes `VaultStats`
+    /// (credential count, weak/old counts, age percentiles) first,
+    /// so anything persisted — and anything `vault()` hands back afterward —
+    /// reflects the vault's current contents rather than whatever was last
+    /// computed.
+    fn save(&mut self) -> Result<()> {
+        self.state.vault.update_stats();
 
-        // Encrypt vault data
-        let (ciphertext, nonce) = encrypt(&vault_json, key)?;
+        let vault_json = serde_json::to_vec(&self.state.vault)?;
+        let (ciphertext, nonce) =
+            encrypt(&vault_json, &self.state.master_key, self.state.algorithm)?;
 
-        // Create encrypted vault structure
         let encrypted_vault = EncryptedVault {
-            salt: salt.to_vec(),
+            salt: self.state.salt.clone(),
             nonce,
             ciphertext,
             version: VAULT_VERSION,
+            algorithm: self.state.algorithm,
+            kdf_params: self.state.kdf_params,
         };
 
-        // Serialize and write to disk
         let encrypted_data = serde_json::to_vec(&encrypted_vault)?;
-        fs::write(&self.vault_path, encrypted_data)?;
-
-        Ok(())
-    }
+        self.storage.write(&encrypted_data)?;
 
-    /// Ensure vault is unlocked
-    fn ensure_unlocked(&self) -> Result<()> {
-        if self.vault.is_none() {
-            return Err(PasswordManagerError::InvalidInput(
-                "Vault is locked. Unlock first.".to_string(),
-            ));
-        }
         Ok(())
     }
 
     /// Add a new credential
     pub fn add_credential(&mut self, credential: Credential) -> Result<()> {
-        self.ensure_unlocked()?;
-
-        let vault = self.vault.as_mut().unwrap();
-
-        // Check if credential already exists
-        if vault.get_credential(&credential.service).is_some() {
+        if self.state.vault.get_credential(&credential.service).is_some() {
             return Err(PasswordManagerError::CredentialAlreadyExists(
                 credential.service.clone(),
             ));
         }
 
-        vault
+        let service = credential.service.clone();
+        self.state
+            .vault
             .add_credential(credential)
             .map_err(PasswordManagerError::InvalidInput)?;
+        self.state
+            .vault
+            .log_operation("add".to_string(), Some(service), true);
 
-        // Re-read salt from existing vault
-        let encrypted_data = fs::read(&self.vault_path)?;
-        let encrypted_vault: EncryptedVault = serde_json::from_slice(&encrypted_data)?;
-
-        self.save(&encrypted_vault.salt)?;
-        Ok(())
+        self.save()
     }
 
     /// Get a credential by service name
     pub fn get_credential(&self, service: &str) -> Result<&Credential> {
-        self.ensure_unlocked()?;
-
-        let vault = self.vault.as_ref().unwrap();
-        vault
+        self.state
+            .vault
             .get_credential(service)
             .ok_or_else(|| PasswordManagerError::CredentialNotFound(service.to_string()))
     }
 
     /// Update a credential's password
     pub fn update_credential(&mut self, service: &str, new_password: String) -> Result<()> {
-        self.ensure_unlocked()?;
-
-        let vault = self.vault.as_mut().unwrap();
-        let credential = vault
+        let credential = self
+            .state
+            .vault
             .get_credential_mut(service)
             .ok_or_else(|| PasswordManagerError::CredentialNotFound(service.to_string()))?;
 
         credential.update_password(new_password);
+        self.state
+            .vault
+            .log_operation("update".to_string(), Some(service.to_string()), true);
 
-        // Re-read salt from existing vault
-        let encrypted_data = fs::read(&self.vault_path)?;
-        let encrypted_vault: EncryptedVault = serde_json::from_slice(&encrypted_data)?;
+        self.save()
+    }
 
-        self.save(&encrypted_vault.salt)?;
-        Ok(())
+    /// Set (or clear, with `None`) a credential's TOTP secret
+    pub fn set_totp_secret(&mut self, service: &str, totp_secret: Option<String>) -> Result<()> {
+        let credential = self
+            .state
+            .vault
+            .get_credential_mut(service)
+            .ok_or_else(|| PasswordManagerError::CredentialNotFound(service.to_string()))?;
+
+        credential.totp_secret = totp_secret;
+        self.state
+            .vault
+            .log_operation("set_totp_secret".to_string(), Some(service.to_string()), true);
+
+        self.save()
     }
 
     /// Remove a credential
     pub fn remove_credential(&mut self, service: &str) -> Result<()> {
-        self.ensure_unlocked()?;
-
-        let vault = self.vault.as_mut().unwrap();
-        vault
+        self.state
+            .vault
             .remove_credential(service)
             .map_err(PasswordManagerError::CredentialNotFound)?;
+        self.state
+            .vault
+            .log_operation("remove".to_string(), Some(service.to_string()), true);
 
-        // Re-read salt from existing vault
-        let encrypted_data = fs::read(&self.vault_path)?;
-        let encrypted_vault: EncryptedVault = serde_json::from_slice(&encrypted_data)?;
-
-        self.save(&encrypted_vault.salt)?;
-        Ok(())
+        self.save()
     }
 
     /// Search credentials by query
-    pub fn search(&self, query: &str) -> Result<Vec<&Credential>> {
-        self.ensure_unlocked()?;
-
-        let vault = self.vault.as_ref().unwrap();
-        Ok(vault.search(query))
+    pub fn search(&self, query: &str) -> Vec<&Credential> {
+        self.state.vault.search(query)
     }
 
     /// List all credentials
-    pub fn list_all(&self) -> Result<&[Credential]> {
-        self.ensure_unlocked()?;
+    pub fn list_all(&self) -> &[Credential] {
+        self.state.vault.list_all()
+    }
 
-        let vault = self.vault.as_ref().unwrap();
-        Ok(vault.list_all())
+    /// Borrows the decrypted vault itself, for callers (e.g. `analytics`)
+    /// that need more than a flat credential list.
+    pub fn vault(&self) -> &Vault {
+        &self.state.vault
     }
 
-    /// Lock the vault (clear from memory)
-    pub fn lock(&mut self) {
-        self.vault = None;
-        self.master_key = None;
+    /// Returns the audit log, oldest first, alongside the result of
+    /// verifying its hash chain — `Err(index)` names the first entry whose
+    /// back-link or hash doesn't check out.
+    pub fn audit_log(&self) -> (&[AuditLogEntry], std::result::Result<(), usize>) {
+        (&self.state.vault.audit_log, self.state.vault.verify_audit_chain())
     }
-}
 
-impl Drop for VaultManager {
-    fn drop(&mut self) {
-        // Ensure sensitive data is cleared on drop
-        self.lock();
+    /// Audits every stored credential's password for breach exposure and
+    /// commonness. Passwords are checked via `batch_check_passwords`, which
+    /// groups them by shared hash prefix so the whole vault costs one HIBP
+    /// range request per distinct prefix rather than one per credential.
+    pub fn audit_all(&self) -> Vec<AuditEntry> {
+        let passwords: Vec<String> = self
+            .state
+            .vault
+            .credentials
+            .iter()
+            .map(|c| c.password.clone())
+            .collect();
+        let results = batch_check_passwords(&passwords);
+
+        self.state
+            .vault
+            .credentials
+            .iter()
+            .zip(results)
+            .map(|(cred, (_, is_breached, breach_count, is_common))| AuditEntry {
+                service: cred.service.clone(),
+                is_breached,
+                breach_count,
+                is_common,
+            })
+            .collect()
+    }
+
+    /// Rotates the master password: verifies `current` re-derives the same
+    /// key this manager was unlocked with, then generates a fresh salt,
+    /// derives a new key from `new`, and re-encrypts the already-decrypted
+    /// vault under it. The old key is dropped (and zeroized and unlocked,
+    /// being a `LockedBytes`) as soon as it's replaced. The actual write goes through
+    /// `VaultStorage::write`; `FileStorage` writes to a temp file and renames
+    /// it into place, so a failure mid-rotation can't corrupt the vault file.
+    pub fn change_master_password(&mut self, current: &str, new: &str) -> Result<()> {
+        let current_key =
+            derive_key_with_params(current, &self.state.salt, self.state.kdf_params)?;
+        if current_key[..] != self.state.master_key[..] {
+            return Err(PasswordManagerError::InvalidMasterPassword);
+        }
+
+        let new_salt = generate_salt();
+        let new_key = derive_key_with_params(new, &new_salt, self.state.kdf_params)?;
+
+        self.state.salt = new_salt;
+        self.state.master_key = new_key;
+        self.state
+            .vault
+            .log_operation("change_master_password".to_string(), None, true);
+
+        self.save()
+    }
+
+    /// Re-runs Argon2id calibration for the machine this vault is currently
+    /// unlocked on and re-encrypts under the new parameters, so a vault
+    /// created on an underpowered device can be strengthened later (or one
+    /// calibrated on a fast machine won't lock a slower one out of a
+    /// reasonable unlock time). `master_password` is re-verified against the
+    /// vault's current parameters before anything is changed.
+    pub fn recalibrate(&mut self, master_password: &str) -> Result<()> {
+        let new_params = calibrate_kdf_params(CALIBRATION_TARGET, CALIBRATION_MEMORY_CEILING_KIB);
+
+        // The current-params verification derive and the new-params derive
+        // don't depend on each other, so a 2-worker Hasher pool runs them at
+        // the same time instead of paying both Argon2 costs back to back.
+        let max_m_cost = self.state.kdf_params.m_cost.max(new_params.m_cost);
+        let hasher = Hasher::new(2, max_m_cost);
+        let mut results = hasher
+            .submit_batch(&[
+                (
+                    master_password.to_string(),
+                    self.state.salt.clone(),
+                    self.state.kdf_params,
+                ),
+                (master_password.to_string(), self.state.salt.clone(), new_params),
+            ])
+            .into_iter();
+        let current_key = results.next().expect("submitted exactly two jobs")?;
+        let new_key = results.next().expect("submitted exactly two jobs")?;
+
+        if current_key[..] != self.state.master_key[..] {
+            return Err(PasswordManagerError::InvalidMasterPassword);
+        }
+
+        self.state.kdf_params = new_params;
+        self.state.master_key = new_key;
+        self.state
+            .vault
+            .log_operation("recalibrate".to_string(), None, true);
+
+        self.save()
+    }
+
+    /// Freezes an immutable snapshot of the current credentials and
+    /// settings and persists it, returning the generation number a later
+    /// `rollback_to_snapshot` call would need to restore it.
+    pub fn snapshot(&mut self) -> Result<u64> {
+        let snapshot = self.state.vault.freeze();
+        self.save()?;
+        Ok(snapshot.generation)
+    }
+
+    /// Lists the vault's retained snapshot generations, oldest first.
+    pub fn list_snapshots(&self) -> &[VaultSnapshot] {
+        self.state.vault.snapshots()
+    }
+
+    /// Restores credentials and settings from a previously frozen
+    /// generation and persists the result. Fails if that generation has
+    /// already been evicted from the retention ring (or never existed).
+    pub fn rollback_to_snapshot(&mut self, generation: u64) -> Result<()> {
+        self.state
+            .vault
+            .rollback(generation)
+            .map_err(PasswordManagerError::InvalidInput)?;
+
+        self.save()
+    }
+
+    /// Exports all credentials to `path` in the given format. `Format::Native`
+    /// writes an encrypted blob in the same format this vault persists
+    /// through its storage backend (freshly encrypted, under a new nonce),
+    /// just to an arbitrary file; `Format::BitwardenJson`
+    /// writes Bitwarden's plaintext export schema, for migrating into (or
+    /// out of) Bitwarden; `Format::Csv` writes a plaintext
+    /// `service,username,password,url,notes,tags,favorite` spreadsheet
+    /// export (passwords included, same as the other two formats).
+    pub fn export(&self, format: Format, path: &Path) -> Result<()> {
+        match format {
+            Format::Native => {
+                let vault_json = serde_json::to_vec(&self.state.vault)?;
+                let (ciphertext, nonce) =
+                    encrypt(&vault_json, &self.state.master_key, self.state.algorithm)?;
+                let encrypted_vault = EncryptedVault {
+                    salt: self.state.salt.clone(),
+                    nonce,
+                    ciphertext,
+                    version: VAULT_VERSION,
+                    algorithm: self.state.algorithm,
+                    kdf_params: self.state.kdf_params,
+                };
+                fs::write(path, serde_json::to_vec(&encrypted_vault)?)?;
+            }
+            Format::BitwardenJson => {
+                let json = credentials_to_bitwarden_json(&self.state.vault.credentials)?;
+                fs::write(path, json)?;
+            }
+            Format::Csv => {
+                let mut csv = Vec::new();
+                self.state.vault.export_csv(&mut csv, false)?;
+                fs::write(path, csv)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Imports credentials from `path` in the given format, skipping any
+    /// whose service name already exists in this vault (or is repeated
+    /// within the import itself). Returns the number of credentials
+    /// actually imported. For `Format::Csv`, a service repeated within the
+    /// file, or any malformed row, fails the whole import instead (see
+    /// `Vault::import_csv`).
+    pub fn import(&mut self, format: Format, path: &Path) -> Result<usize> {
+        let imported = match format {
+            Format::Native => {
+                let data = fs::read(path)?;
+                let encrypted_vault: EncryptedVault = serde_json::from_slice(&data)?;
+                let decrypted = decrypt(
+                    &encrypted_vault.ciphertext,
+                    &self.state.master_key,
+                    &encrypted_vault.nonce,
+                    encrypted_vault.algorithm,
+                )?;
+                let vault: Vault = serde_json::from_slice(&decrypted)
+                    .map_err(|_| PasswordManagerError::InvalidMasterPassword)?;
+                vault.credentials
+            }
+            Format::BitwardenJson => {
+                let data = fs::read_to_string(path)?;
+                bitwarden_json_to_credentials(&data)?
+            }
+            Format::Csv => {
+                let file = fs::File::open(path)?;
+                let mut staging = Vault::new();
+                staging.import_csv(BufReader::new(file)).map_err(|errors| {
+                    let joined = errors
+                        .into_iter()
+                        .map(|(line, msg)| format!("line {}: {}", line, msg))
+                        .collect::<Vec<_>>()
+                        .join("; ");
+                    PasswordManagerError::InvalidInput(joined)
+                })?;
+                staging.credentials
+            }
+        };
+
+        let imported_count = if imported.is_empty() {
+            0
+        } else {
+            // A CSV/Bitwarden export can carry thousands of rows; checking
+            // each one against the existing vault (and against the rest of
+            // the batch) is independent work per credential, so it's split
+            // across worker threads over a `ConcurrentVault` instead of
+            // walked one row at a time. `add_credential` takes the store's
+            // write lock only long enough to insert a key, so a duplicate
+            // service name losing the race to another worker is reported as
+            // an error and simply skipped, same as the old serial loop.
+            let store = Arc::new(ConcurrentVault::from_credentials(
+                self.state.vault.credentials.clone(),
+            ));
+            let worker_count = std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+                .min(imported.len());
+            let chunk_size = imported.len().div_ceil(worker_count);
+
+            let mut new_count = 0;
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = imported
+                    .chunks(chunk_size)
+                    .map(|chunk| {
+                        let store = Arc::clone(&store);
+                        scope.spawn(move || {
+                            chunk
+                                .iter()
+                                .filter(|credential| {
+                                    store.add_credential((*credential).clone()).is_ok()
+                                })
+                                .count()
+                        })
+                    })
+                    .collect();
+                for handle in handles {
+                    new_count += handle.join().expect("import worker thread panicked");
+                }
+            });
+
+            self.state.vault.credentials = Arc::try_unwrap(store)
+                .unwrap_or_else(|_| panic!("all worker threads joined above"))
+                .into_credentials();
+            new_count
+        };
+
+        if imported_count > 0 {
+            self.state.vault.log_operation(
+                format!("import ({} credential(s))", imported_count),
+                None,
+                true,
+            );
+            self.save()?;
+        }
+
+        Ok(imported_count)
+    }
+
+    /// Lock the vault, zeroizing the master key and consuming the unlocked
+    /// manager in the process.
+    pub fn lock(self) -> VaultManager<Locked> {
+        VaultManager {
+            storage: self.storage,
+            state: Locked,
+        }
     }
 }
 
@@ -264,15 +573,15 @@ mod tests {
         drop(temp_file);
         let _ = fs::remove_file(&vault_path);
 
-        let mut manager = VaultManager::new(vault_path.clone());
+        let manager = VaultManager::new(vault_path.clone());
 
         // Initialize
-        manager.initialize("test_password").unwrap();
+        let manager = manager.initialize("test_password").unwrap();
         assert!(vault_path.exists());
 
         // Lock and unlock
-        manager.lock();
-        manager.unlock("test_password").unwrap();
+        let manager = manager.lock();
+        let mut manager = manager.unlock("test_password").unwrap();
 
         // Add credential
         let cred = Credential::new(